@@ -4,7 +4,7 @@ const WIDTH: usize = 4096;
 fn main() -> Result<(), std::io::Error> {
     let (start, end) = (-0.5, 0.5);
     let pts = points(start, end);
-    let graph = GraphData::Width3D(pts, start, start, end, end);
+    let graph = GraphData::Width3D(pts, start, start, end, end, Some(WIDTH));
     let name = Name::new("sin(1/z)".to_string());
     let mut plot = Graph::new(vec![graph], vec![name], true, start, end);
     plot.set_mode(GraphMode::DomainColoring);
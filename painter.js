@@ -9,6 +9,17 @@ export function line_segment(a, b, x, y, w, c) {
     ctx.lineTo(x, y);
     ctx.stroke();
 }
+export function line_dashed(a, b, x, y, w, c, dash, phase) {
+    ctx.beginPath();
+    ctx.strokeStyle = c;
+    ctx.lineWidth = w;
+    ctx.setLineDash(dash);
+    ctx.lineDashOffset = phase;
+    ctx.moveTo(a, b);
+    ctx.lineTo(x, y);
+    ctx.stroke();
+    ctx.setLineDash([]);
+}
 export function circle(x, y, r1, r2, w, c) {
     ctx.beginPath();
     ctx.strokeStyle = c;
@@ -24,6 +35,28 @@ export function fill_rect(a, b, x, y, c) {
     ctx.fillStyle = c;
     ctx.fillRect(a, b, x, y);
 }
+export function fill_polygon(points, c) {
+    if (points.length < 6) {
+        return;
+    }
+    ctx.beginPath();
+    ctx.fillStyle = c;
+    ctx.moveTo(points[0], points[1]);
+    for (let i = 2; i < points.length; i += 2) {
+        ctx.lineTo(points[i], points[i + 1]);
+    }
+    ctx.closePath();
+    ctx.fill();
+}
+export function clip(a, b, x, y) {
+    ctx.save();
+    ctx.beginPath();
+    ctx.rect(a, b, x, y);
+    ctx.clip();
+}
+export function unclip() {
+    ctx.restore();
+}
 export function text_bounds(s) {
     ctx.font = "18px monospace";
     const m = ctx.measureText(s);
@@ -0,0 +1,336 @@
+//!layouts of saves and share strings written before they carried a version,
+//!bitcode is not self describing so they can only be read back with the exact types they were written with
+use crate::types::*;
+use base64::Engine;
+use serde::Deserialize;
+///version of the full save layout, bump it whenever a serialized field of Graph changes
+pub(crate) const SAVE_VERSION: u8 = 1;
+///version of the share string layout, bump it whenever a field of GraphTiny changes
+pub(crate) const TINY_VERSION: u8 = 1;
+///splits the version off a payload and decompresses the rest, unversioned payloads are version 0
+fn payload(s: &str, len: usize) -> Option<(u8, Vec<u8>)> {
+    let (version, s) = match s.split_once('.') {
+        Some((v, s)) => (v.parse::<u8>().ok()?, s),
+        None => (0, s),
+    };
+    let s = base64::prelude::BASE64_URL_SAFE_NO_PAD.decode(s).ok()?;
+    Some((version, zstd::bulk::decompress(&s, len).ok()?))
+}
+///decodes the payload of a full save, len being its uncompressed length
+pub(crate) fn decode_save(s: &str, len: usize) -> Option<Graph> {
+    let (version, data) = payload(s, len)?;
+    match version {
+        0 => bitcode::deserialize::<GraphV0>(&data).ok().map(Graph::from),
+        SAVE_VERSION => bitcode::deserialize(&data).ok(),
+        _ => None,
+    }
+}
+///decodes the payload of a share string, len being its uncompressed length
+pub(crate) fn decode_tiny(s: &str, len: usize) -> Option<GraphTiny> {
+    let (version, data) = payload(s, len)?;
+    match version {
+        0 => bitcode::deserialize::<GraphTinyV0>(&data)
+            .ok()
+            .map(GraphTiny::from),
+        TINY_VERSION => bitcode::deserialize(&data).ok(),
+        _ => None,
+    }
+}
+#[derive(Deserialize)]
+struct ColorV0 {
+    r: u8,
+    g: u8,
+    b: u8,
+}
+impl From<ColorV0> for Color {
+    fn from(c: ColorV0) -> Self {
+        Color::new(c.r, c.g, c.b)
+    }
+}
+#[derive(Deserialize)]
+struct NameV0 {
+    vars: Vec<String>,
+    name: String,
+    show: Show,
+}
+impl From<NameV0> for Name {
+    fn from(n: NameV0) -> Self {
+        let mut name = Name::new(n.name);
+        name.vars = n.vars;
+        name.show = n.show;
+        name
+    }
+}
+fn colors(c: Vec<ColorV0>) -> Vec<Color> {
+    c.into_iter().map(Color::from).collect()
+}
+#[derive(Deserialize)]
+struct GraphV0 {
+    names: Vec<NameV0>,
+    name_updated: Option<usize>,
+    font_size: f32,
+    font_width: f32,
+    line_width: f32,
+    point_size: f32,
+    #[cfg(feature = "skia")]
+    image_format: crate::ui::ImageFormat,
+    fast_3d: bool,
+    fast_3d_move: bool,
+    reduced_move: bool,
+    bound: Vec2,
+    is_complex: bool,
+    offset3d: Vec3,
+    offset: Vec2,
+    angle: Vec2,
+    ignore_bounds: bool,
+    zoom: Vec2,
+    zoom_3d: Vec3,
+    slice: isize,
+    var: Vec2,
+    log_scale: bool,
+    box_size: f64,
+    domain_alternate: bool,
+    screen: Vec2,
+    screen_offset: Vec2,
+    delta: f64,
+    show: Show,
+    anti_alias: bool,
+    color_depth: DepthColor,
+    show_box: bool,
+    main_colors: Vec<ColorV0>,
+    alt_colors: Vec<ColorV0>,
+    axis_color: ColorV0,
+    blacklist_graphs: Vec<usize>,
+    axis_color_light: ColorV0,
+    background_color: ColorV0,
+    text_color: ColorV0,
+    mouse_position: Option<Vec2>,
+    mouse_moved: bool,
+    disable_lines: bool,
+    disable_axis: bool,
+    disable_coord: bool,
+    view_x: bool,
+    graph_mode: GraphMode,
+    is_3d: bool,
+    is_3d_data: bool,
+    angle_type: Angle,
+    last_interact: Option<Vec2>,
+    last_right_interact: Option<Vec2>,
+    recalculate: bool,
+    name_modified: bool,
+    lines: Lines,
+    ruler_pos: Option<Vec2>,
+    prec: f64,
+    mouse_held: bool,
+    mult: f64,
+    line_major: usize,
+    line_minor: usize,
+    draw_offset: Pos,
+    cos_phi: f64,
+    sin_phi: f64,
+    cos_theta: f64,
+    sin_theta: f64,
+    select: Option<(usize, usize, Option<bool>)>,
+    text_box: Option<(usize, usize)>,
+    side_slider: Option<usize>,
+    side_drag: Option<(usize, Option<usize>)>,
+    last_multi: bool,
+    side_bar_width: f64,
+    history: Vec<Change>,
+    history_pos: usize,
+    text_scroll_pos: (usize, usize),
+    only_real: bool,
+    menu: Menu,
+    side_height: f32,
+    min_side_width: f64,
+    min_screen_width: f64,
+    target_side_ratio: f64,
+    bracket_color: Vec<ColorV0>,
+    select_color: ColorV0,
+    #[cfg(feature = "arboard")]
+    wait_frame: bool,
+    save_file: String,
+    save_num: Option<usize>,
+}
+impl From<GraphV0> for Graph {
+    fn from(v: GraphV0) -> Self {
+        let mut g = Graph::default();
+        g.names = v.names.into_iter().map(Name::from).collect();
+        g.name_updated = v.name_updated;
+        g.font_size = v.font_size;
+        g.font_width = v.font_width;
+        g.line_width = v.line_width;
+        g.point_size = v.point_size;
+        #[cfg(feature = "skia")]
+        {
+            g.image_format = v.image_format;
+        }
+        g.fast_3d = v.fast_3d;
+        g.fast_3d_move = v.fast_3d_move;
+        g.reduced_move = v.reduced_move;
+        g.bound = v.bound;
+        g.is_complex = v.is_complex;
+        g.offset3d = v.offset3d;
+        g.offset = v.offset;
+        g.angle = v.angle;
+        g.ignore_bounds = v.ignore_bounds;
+        g.zoom = v.zoom;
+        g.zoom_3d = v.zoom_3d;
+        g.slice = v.slice;
+        g.var = v.var;
+        g.log_scale = v.log_scale;
+        g.box_size = v.box_size;
+        g.domain_alternate = v.domain_alternate;
+        g.screen = v.screen;
+        g.screen_offset = v.screen_offset;
+        g.delta = v.delta;
+        g.show = v.show;
+        g.anti_alias = v.anti_alias;
+        g.color_depth = v.color_depth;
+        g.show_box = v.show_box;
+        g.main_colors = colors(v.main_colors);
+        g.alt_colors = colors(v.alt_colors);
+        g.axis_color = v.axis_color.into();
+        g.blacklist_graphs = v.blacklist_graphs;
+        g.axis_color_light = v.axis_color_light.into();
+        g.background_color = v.background_color.into();
+        g.text_color = v.text_color.into();
+        g.mouse_position = v.mouse_position;
+        g.mouse_moved = v.mouse_moved;
+        g.disable_lines = v.disable_lines;
+        g.disable_axis = v.disable_axis;
+        g.disable_coord = v.disable_coord;
+        g.view_x = v.view_x;
+        g.graph_mode = v.graph_mode;
+        g.is_3d = v.is_3d;
+        g.is_3d_data = v.is_3d_data;
+        g.angle_type = v.angle_type;
+        g.last_interact = v.last_interact;
+        g.last_right_interact = v.last_right_interact;
+        g.recalculate = v.recalculate;
+        g.name_modified = v.name_modified;
+        g.lines = v.lines;
+        g.ruler_pos = v.ruler_pos;
+        g.prec = v.prec;
+        g.mouse_held = v.mouse_held;
+        g.mult = v.mult;
+        g.line_major = v.line_major;
+        g.line_minor = v.line_minor;
+        g.draw_offset = v.draw_offset;
+        g.cos_phi = v.cos_phi;
+        g.sin_phi = v.sin_phi;
+        g.cos_theta = v.cos_theta;
+        g.sin_theta = v.sin_theta;
+        g.select = v.select;
+        g.text_box = v.text_box;
+        g.side_slider = v.side_slider;
+        g.side_drag = v.side_drag;
+        g.last_multi = v.last_multi;
+        g.side_bar_width = v.side_bar_width;
+        g.history = v.history;
+        g.history_pos = v.history_pos;
+        g.text_scroll_pos = v.text_scroll_pos;
+        g.only_real = v.only_real;
+        g.menu = v.menu;
+        g.side_height = v.side_height;
+        g.min_side_width = v.min_side_width;
+        g.min_screen_width = v.min_screen_width;
+        g.target_side_ratio = v.target_side_ratio;
+        g.bracket_color = colors(v.bracket_color);
+        g.select_color = v.select_color.into();
+        #[cfg(feature = "arboard")]
+        {
+            g.wait_frame = v.wait_frame;
+        }
+        g.save_file = v.save_file;
+        g.save_num = v.save_num;
+        g
+    }
+}
+#[derive(Deserialize)]
+struct GraphTinyV0 {
+    names: Vec<NameV0>,
+    bound: (f32, f32),
+    prec: f32,
+    is_complex: bool,
+    offset3d: Option<(f32, f32, f32)>,
+    offset: Option<(f32, f32)>,
+    zoom: Option<(f32, f32)>,
+    zoom_3d: Option<(f32, f32, f32)>,
+    slice: i8,
+    var: (f32, f32),
+    log_scale: bool,
+    domain_alternate: bool,
+    color_depth: DepthColor,
+    blacklist_graphs: Vec<u8>,
+    view_x: bool,
+    graph_mode: GraphMode,
+    only_real: bool,
+}
+impl From<GraphTinyV0> for GraphTiny {
+    fn from(v: GraphTinyV0) -> Self {
+        GraphTiny {
+            names: v.names.into_iter().map(Name::from).collect(),
+            bound: v.bound,
+            prec: v.prec,
+            is_complex: v.is_complex,
+            offset3d: v.offset3d,
+            offset: v.offset,
+            zoom: v.zoom,
+            zoom_3d: v.zoom_3d,
+            slice: v.slice,
+            var: v.var,
+            log_scale: v.log_scale,
+            domain_alternate: v.domain_alternate,
+            color_depth: v.color_depth,
+            blacklist_graphs: v.blacklist_graphs,
+            view_x: v.view_x,
+            graph_mode: v.graph_mode,
+            only_real: v.only_real,
+            ..Default::default()
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    //written by the layout before saves carried a version
+    const SAVE_V0: &str = "KLUv_WBZAO0EALJHGR6gqwMY572qB8wqnyv8xVK6JDj2Gu02oMMu__mTLVP2r-tf766_NyA9BAqiEKFV7f-apnlOe3o67vcH_EN_96uOChWq_KyJY5TpKDu5ZcthRI2jkcGYYKSykc0JZduFRDxOFK0BGCDQiKrkARwIq3eEG5rhZgGATGUbTNmXcrQdQLCFAVZADQLFdkOx9I0IkgscPKGJMZRx4SU";
+    const TINY_V0: &str = "NjY@KLUv_SBCxQEAMkMLFdClMQAAgqiI2Dj_bnExFv9zqCIypdNPl9HP6Tndy6gleqZKFGUowmEgQKAUAgBFGaDglgE";
+    #[test]
+    fn decodes_unversioned_save() {
+        let graph = decode_save(SAVE_V0, 345).unwrap();
+        assert_eq!(graph.names.len(), 1);
+        assert_eq!(graph.names[0].name, "sin(x)");
+        assert_eq!(graph.main_colors[0], Color::new(1, 2, 3));
+        assert_eq!(graph.line_width, 7.0);
+        assert_eq!(graph.bound, Vec2::new(-3.0, 5.0));
+    }
+    #[test]
+    fn decodes_unversioned_share_string() {
+        let tiny = GraphTiny::try_from(&TINY_V0.to_string()).unwrap();
+        let names = tiny
+            .names
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["sin(x)", "x^2"]);
+        assert_eq!(tiny.blacklist_graphs, [1]);
+        assert_eq!(tiny.graph_mode, GraphMode::DomainColoring);
+        assert!(tiny.only_real && tiny.is_complex);
+        assert_eq!(tiny.bound, (-3.0, 5.0));
+    }
+    #[test]
+    fn versioned_save_round_trips() {
+        let mut graph = Graph::new(vec![], vec![Name::new("x".to_string())], false, -1.0, 1.0);
+        graph.line_width = 3.0;
+        let data = bitcode::serialize(&graph).unwrap();
+        let s = format!(
+            "{SAVE_VERSION}.{}",
+            base64::prelude::BASE64_URL_SAFE_NO_PAD.encode(zstd::bulk::compress(&data, 3).unwrap())
+        );
+        let back = decode_save(&s, data.len()).unwrap();
+        assert_eq!((back.names[0].name.as_str(), back.line_width), ("x", 3.0));
+        assert!(decode_save(&format!("{}.{}", SAVE_VERSION + 1, &s[2..]), data.len()).is_none());
+    }
+}
@@ -1,6 +1,12 @@
+#[cfg(feature = "serde")]
+mod legacy;
+#[cfg(feature = "record")]
+#[cfg(any(feature = "skia", feature = "tiny-skia"))]
+mod recorder;
 mod sidebar;
 #[cfg(feature = "skia-vulkan")]
 pub mod skia_vulkan;
+mod subplots;
 pub mod types;
 mod ui;
 use crate::types::*;
@@ -8,13 +14,22 @@ use crate::ui::Painter;
 #[cfg(feature = "serde")]
 use base64::Engine;
 #[cfg(feature = "rayon")]
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator,
+    IntoParallelRefMutIterator, ParallelIterator,
+};
+#[cfg(feature = "rayon")]
 use rayon::slice::ParallelSliceMut;
 use std::f64::consts::{PI, TAU};
 #[cfg(feature = "serde")]
 use std::io::BufRead;
 fn is_3d(data: &[GraphData]) -> bool {
-    data.iter()
-        .any(|c| matches!(c, GraphData::Width3D(_, _, _, _, _) | GraphData::Coord3D(_)))
+    data.iter().any(|c| {
+        matches!(
+            c,
+            GraphData::Width3D(_, _, _, _, _, _) | GraphData::Coord3D(_)
+        )
+    })
 }
 //TODO removing/adding lines should not move disabled spots
 #[cfg(target_arch = "wasm32")]
@@ -91,7 +106,7 @@ impl Graph {
     #[cfg(any(feature = "skia", feature = "tiny-skia", feature = "wasm-draw"))]
     ///is cursor dragging a value or not
     pub fn is_drag(&self) -> bool {
-        self.side_drag.is_some() || self.side_slider.is_some()
+        self.side_drag.is_some() || self.side_slider.is_some() || self.annotation_drag.is_some()
     }
     #[cfg(any(feature = "skia", feature = "tiny-skia-text"))]
     ///sets font
@@ -136,6 +151,7 @@ impl Graph {
         self.axis_color_light = Color::splat(35);
         self.set_text_color(Color::splat(255));
         self.background_color = Color::splat(0);
+        self.select_color = Color::rgba(96, 96, 255, 128);
     }
     //use light mode default colors
     pub fn set_light_mode(&mut self) {
@@ -143,6 +159,17 @@ impl Graph {
         self.axis_color_light = Color::splat(220);
         self.set_text_color(Color::splat(0));
         self.background_color = Color::splat(255);
+        self.select_color = Color::rgba(128, 128, 255, 96);
+    }
+    ///sets the opacity of every main and alt color
+    pub fn set_graph_alpha(&mut self, alpha: u8) {
+        for c in self
+            .main_colors
+            .iter_mut()
+            .chain(self.alt_colors.iter_mut())
+        {
+            c.a = alpha
+        }
     }
     ///sets font size
     pub fn set_font_size(&mut self, size: f32) {
@@ -151,20 +178,88 @@ impl Graph {
     }
     ///removes data in nth slot
     pub fn remove_data(&mut self, n: usize) -> GraphData {
+        self.data_modified();
         self.data.remove(n)
     }
     ///takes the data storage object
     pub fn take_data(&mut self) -> Vec<GraphData> {
+        self.data_modified();
         std::mem::take(&mut self.data)
     }
     ///insert and replace data into nth slot
     pub fn insert_data(&mut self, data: GraphData, n: usize) {
+        self.data_modified();
         self.data.insert(n, data);
     }
+    ///drops everything cached from Graph.data, call after changing it in place
+    pub fn data_modified(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        self.partial_redraw = false;
+        self.binned.clear();
+        self.decimated.clear();
+        self.cache = None;
+        self.hover_view = None;
+    }
     ///sets data and resets domain coloring cache
     pub fn set_data(&mut self, data: Vec<GraphData>) {
-        self.data = data;
-        self.cache = None;
+        self.data_modified();
+        let old = std::mem::replace(&mut self.data, data);
+        if self.ghost_next && self.onion_skin > 0 {
+            self.ghosts.insert(0, old);
+            self.ghosts.truncate(self.onion_skin);
+        } else {
+            self.ghosts.clear();
+        }
+        self.ghost_next = false;
+    }
+    ///appends points to the nth data set, replacing it with a Coord if it isn't one,
+    ///if capacity is given the oldest points are dropped an eighth of capacity at a time
+    ///so at most capacity points are kept,
+    ///the next update will only draw the new points if nothing else changed
+    pub fn append_points(&mut self, n: usize, points: &[(f64, Complex)], capacity: Option<usize>) {
+        let left = self.to_coord(Pos::new(0.0, 0.0)).0;
+        self.hover_view = None;
+        if let Some(d) = self.decimated.get_mut(n) {
+            *d = None
+        }
+        if let Some(b) = self.binned.get_mut(n) {
+            *b = None
+        }
+        while self.data.len() <= n {
+            self.data.push(GraphData::None);
+            self.partial_redraw = false;
+        }
+        if !matches!(self.data[n], GraphData::Coord(_)) {
+            self.data[n].set_type(GraphType::Coord, capacity.unwrap_or(points.len()));
+            self.partial_redraw = false;
+        }
+        let GraphData::Coord(data) = &mut self.data[n] else {
+            unreachable!()
+        };
+        let points = if let Some(cap) = capacity {
+            let points = &points[points.len().saturating_sub(cap)..];
+            let excess = (data.len() + points.len()).saturating_sub(cap);
+            if excess > 0 {
+                let trim = excess.max(cap / 8).min(data.len());
+                if !data
+                    .get(..=trim)
+                    .is_some_and(|d| d.iter().all(|(x, _)| *x < left))
+                {
+                    self.partial_redraw = false;
+                }
+                data.drain(..trim);
+                for (_, start) in self.appended.iter_mut().filter(|(i, _)| *i == n) {
+                    *start = start.saturating_sub(trim)
+                }
+            }
+            points
+        } else {
+            points
+        };
+        if !self.appended.iter().any(|(i, _)| *i == n) {
+            self.appended.push((n, data.len()));
+        }
+        data.extend_from_slice(points);
     }
     pub(crate) fn reset_offset(&self, width: f64, height: f64) -> Vec2 {
         let (_, _, screen) = self.get_new_screen(width, height, true);
@@ -191,6 +286,13 @@ impl Graph {
         } else {
             Vec2::new(width, height)
         };
+        let rest = screen.x - self.outside_legend_width(screen.y);
+        let source = if self.has_conformal_source() {
+            (rest * 0.5).min(screen.y)
+        } else {
+            0.0
+        };
+        let screen = Vec2::new((rest - source).max(1.0), screen.y);
         (fw, new, screen)
     }
     ///sets screen dimensions
@@ -210,17 +312,21 @@ impl Graph {
         } else {
             0.0
         };
-        self.draw_offset = if !matches!(self.menu, Menu::Normal) && offset && height < width {
-            Pos::new((width - new) as f32, 0.0)
-        } else {
-            Pos::new(0.0, 0.0)
-        };
+        self.draw_offset = self.pane_offset
+            + if !matches!(self.menu, Menu::Normal) && offset && height < width {
+                Pos::new((width - new) as f32, 0.0)
+            } else {
+                Pos::new(0.0, 0.0)
+            };
         let t = Vec2::new(
             self.screen.x * 0.5 - (self.delta * (self.bound.x + self.bound.y) * 0.5),
             self.screen.y * 0.5,
         );
         if t != self.screen_offset && offset {
-            if self.graph_mode == GraphMode::DomainColoring {
+            if matches!(
+                self.graph_mode,
+                GraphMode::DomainColoring | GraphMode::Implicit
+            ) {
                 self.recalculate(None);
             }
             self.screen_offset = t;
@@ -231,8 +337,8 @@ impl Graph {
     }
     ///clears data and domain coloring cache
     pub fn clear_data(&mut self) {
+        self.data_modified();
         self.data.clear();
-        self.cache = None;
     }
     ///resets current 3d view based on the data that is supplied
     pub fn reset_3d(&mut self) {
@@ -250,7 +356,12 @@ impl Graph {
         self.is_3d_data = new;
         match self.graph_mode {
             GraphMode::Normal | GraphMode::Flatten | GraphMode::Polar => self.is_3d = new,
-            GraphMode::Slice | GraphMode::DomainColoring | GraphMode::SlicePolar if !new => {
+            GraphMode::Slice
+            | GraphMode::DomainColoring
+            | GraphMode::SlicePolar
+            | GraphMode::Implicit
+                if !new =>
+            {
                 self.graph_mode = GraphMode::Normal
             }
             GraphMode::Depth => {}
@@ -266,8 +377,10 @@ impl Graph {
             GraphMode::DomainColoring
             | GraphMode::Slice
             | GraphMode::Flatten
-            | GraphMode::SlicePolar => self.is_3d = false,
-            GraphMode::Depth => self.is_3d = true,
+            | GraphMode::SlicePolar
+            | GraphMode::Implicit
+            | GraphMode::ConformalGrid => self.is_3d = false,
+            GraphMode::Depth | GraphMode::RiemannSphere => self.is_3d = true,
             _ => {
                 self.is_3d = self.is_3d_data;
             }
@@ -304,6 +417,7 @@ impl Graph {
             self.name_updated = name;
         }
         self.recalculate = true;
+        self.partial_redraw = false;
     }
     pub(crate) fn name_modified(&mut self, name: Option<usize>) {
         if let Some(n) = self.name_updated.as_mut() {
@@ -312,6 +426,166 @@ impl Graph {
             self.name_updated = name;
         }
         self.name_modified = true;
+        self.partial_redraw = false;
+    }
+    ///sweeps the side bar variable var, defined as in var=1, from start to end over frames frames,
+    ///paused until Graph::play
+    pub fn animate(
+        &mut self,
+        var: String,
+        start: f64,
+        end: f64,
+        frames: usize,
+        playback: Playback,
+    ) {
+        self.animation = Some(Animation::new(var, start, end, frames, playback));
+        self.apply_animation()
+    }
+    ///animates the variable on side bar line i around its current value
+    pub(crate) fn animate_line(&mut self, i: usize) {
+        let Some((var, v)) = self.get_name(i).rsplit_once('=') else {
+            return;
+        };
+        let Ok(v) = v.parse::<f64>() else {
+            return;
+        };
+        let w = v.abs().max(1.0);
+        let (frames, playback) = self
+            .animation
+            .as_ref()
+            .map_or((120, Playback::Bounce), |a| (a.frames, a.playback));
+        let mut a = Animation::new(var.to_string(), v - w, v + w, frames, playback);
+        a.frame = frames / 2;
+        self.animation = Some(a);
+    }
+    pub fn is_playing(&self) -> bool {
+        self.animation.as_ref().is_some_and(|a| a.playing)
+    }
+    pub fn play(&mut self) {
+        if let Some(a) = self.animation.as_mut() {
+            if a.playback == Playback::Once && a.frame >= a.frames {
+                a.frame = 0
+            }
+            a.playing = true;
+        }
+    }
+    pub fn pause(&mut self) {
+        if let Some(a) = self.animation.as_mut() {
+            a.playing = false
+        }
+    }
+    pub fn toggle_play(&mut self) {
+        if self.is_playing() {
+            self.pause()
+        } else {
+            self.play()
+        }
+    }
+    ///moves the animation one frame along its direction of play, or back against it,
+    ///the host is asked for new data through update_res
+    pub fn step_animation(&mut self, along: bool) {
+        if let Some(a) = self.animation.as_mut() {
+            a.step(along);
+            self.apply_animation()
+        }
+    }
+    ///writes the current value of the animation into its side bar variable
+    fn apply_animation(&mut self) {
+        let Some(a) = &self.animation else {
+            return;
+        };
+        let (var, v) = (a.var.clone(), a.value());
+        let mut line = None;
+        let mut i = 0;
+        'outer: for name in self.names.iter_mut() {
+            for s in name.vars.iter_mut().chain(std::iter::once(&mut name.name)) {
+                if s.rsplit_once('=')
+                    .is_some_and(|(a, b)| a == var && b.parse::<f64>().is_ok())
+                {
+                    *s = format!("{var}={v}");
+                    line = Some(i);
+                    break 'outer;
+                }
+                i += 1;
+            }
+        }
+        if line.is_some() {
+            self.name_modified(line);
+            #[cfg(any(feature = "skia", feature = "tiny-skia", feature = "wasm-draw"))]
+            {
+                self.request_redraw = true;
+            }
+        }
+    }
+    ///moves the slice by speed slices, or with slice_play_var the var range by speed 64ths of
+    ///itself in flatten and depth, fractions carry over to the next call
+    pub fn advance_slice(&mut self, speed: f64) {
+        if !self.is_3d_data {
+            return;
+        }
+        match self.graph_mode {
+            GraphMode::Flatten | GraphMode::Depth if self.slice_play_var => {
+                let s = (self.var.y - self.var.x) / 64.0 * speed;
+                self.var.x += s;
+                self.var.y += s;
+            }
+            GraphMode::Slice
+            | GraphMode::SlicePolar
+            | GraphMode::Flatten
+            | GraphMode::Depth
+            | GraphMode::Polar => {
+                self.slice_acc += speed;
+                let n = self.slice_acc.trunc();
+                self.slice_acc -= n;
+                if n == 0.0 {
+                    return;
+                }
+                self.slice += n as isize;
+            }
+            GraphMode::Normal
+            | GraphMode::DomainColoring
+            | GraphMode::Implicit
+            | GraphMode::RiemannSphere
+            | GraphMode::ConformalGrid => return,
+        }
+        self.slice_moved = true;
+        self.recalculate(None);
+        #[cfg(any(feature = "skia", feature = "tiny-skia", feature = "wasm-draw"))]
+        {
+            self.request_redraw = true;
+        }
+    }
+    ///plays the animation by as many frames of Graph.fps as passed since the last call
+    fn tick(&mut self, i: &InputState) {
+        if !self.is_playing() && !self.slice_playing {
+            self.last_tick = None;
+            return;
+        }
+        let now = i.time.or_else(clock);
+        let frames = match (now, self.last_tick) {
+            (Some(now), Some(last)) => ((now - last) * self.fps).clamp(0.0, self.fps),
+            (Some(_), None) => 0.0,
+            (None, _) => 1.0,
+        };
+        self.last_tick = now;
+        self.animation_acc += frames;
+        let n = self.animation_acc.trunc();
+        self.animation_acc -= n;
+        if n > 0.0
+            && let Some(a) = self.animation.as_mut()
+        {
+            for _ in 0..n as usize {
+                a.step(true)
+            }
+            self.apply_animation()
+        }
+        if self.slice_playing && frames > 0.0 {
+            self.advance_slice(self.slice_speed * frames)
+        }
+        #[cfg(any(feature = "skia", feature = "tiny-skia", feature = "wasm-draw"))]
+        {
+            self.request_redraw = true;
+        }
     }
     ///will print the string to the right of the function/var
     pub fn set_constant_eval(&mut self, eval: Vec<(usize, String)>) {
@@ -324,10 +598,28 @@ impl Graph {
         if self.recalculate || self.name_modified {
             self.recalculate = false;
             self.name_modified = false;
+            //only the data answering a slice move becomes a ghost
+            self.ghost_next = std::mem::take(&mut self.slice_moved);
             let prec = self.prec();
             Some((
                 if self.is_3d_data {
                     match self.graph_mode {
+                        GraphMode::ConformalGrid => Bound::Grid(
+                            self.var.x,
+                            self.var.x,
+                            self.var.y,
+                            self.var.y,
+                            Prec::Dimension(
+                                self.conformal_lines.max(2),
+                                (512.0 * prec * self.mult) as usize,
+                            ),
+                        ),
+                        GraphMode::RiemannSphere => {
+                            let n = ((64.0 * prec * self.mult) as usize).max(8);
+                            //open in latitude, the poles themselves map to 0 and infinity
+                            let lat = PI * 0.5 * (1.0 - 1.0 / n as f64);
+                            Bound::Sphere(-PI, -lat, PI, lat, Prec::Dimension(2 * n + 1, n))
+                        }
                         GraphMode::Normal => Bound::Width3D(
                             self.bound.x / self.zoom_3d.x + self.offset3d.x,
                             self.bound.x / self.zoom_3d.y - self.offset3d.y,
@@ -342,7 +634,7 @@ impl Graph {
                             self.bound.y / self.zoom_3d.y - self.offset3d.y,
                             Prec::Mult(self.prec),
                         ),
-                        GraphMode::DomainColoring => {
+                        GraphMode::DomainColoring | GraphMode::Implicit => {
                             let c = self.to_coord(Pos::new(0.0, 0.0));
                             let cf = self.to_coord(self.screen.to_pos());
                             Bound::Width3D(
@@ -481,15 +773,21 @@ impl Graph {
     {
         let mut canvas = std::mem::take(&mut self.canvas);
         if canvas.is_none() {
+            self.partial_redraw = false;
             canvas = Some(get_surface(width as i32, height as i32));
         }
         let Some(canvas) = canvas else { unreachable!() };
         let mut canvas = if (width as i32, height as i32) == (canvas.width(), canvas.height()) {
             canvas
         } else {
+            self.partial_redraw = false;
             get_surface(width as i32, height as i32)
         };
-        self.get_img(width, height, buffer, &mut canvas);
+        if self.can_draw_appended() {
+            self.draw_appended(buffer, &mut canvas)
+        } else {
+            self.get_img(width, height, buffer, &mut canvas);
+        }
         self.canvas = Some(canvas);
     }
     #[cfg(feature = "skia")]
@@ -540,6 +838,17 @@ impl Graph {
         painter.save(_buffer);
     }
     #[cfg(feature = "skia")]
+    #[cfg(not(feature = "skia-vulkan"))]
+    fn draw_appended<T>(&mut self, _buffer: &mut T, surface: &mut skia_safe::Surface)
+    where
+        T: std::ops::DerefMut<Target = [u32]>,
+    {
+        let mut painter = Painter::retained(surface, self.anti_alias, self.draw_offset);
+        self.plot_appended(&mut painter);
+        #[cfg(not(target_arch = "wasm32"))]
+        painter.save(_buffer);
+    }
+    #[cfg(feature = "skia")]
     ///get png data
     pub fn get_png(&mut self, width: u32, height: u32) -> ui::Data {
         self.font_width();
@@ -563,15 +872,129 @@ impl Graph {
     {
         let mut canvas = std::mem::take(&mut self.canvas);
         if canvas.is_none() {
+            self.partial_redraw = false;
             canvas = Some(tiny_skia::Pixmap::new(width, height).unwrap());
         }
         let Some(canvas) = canvas else { unreachable!() };
         let canvas = if (width, height) == (canvas.width(), canvas.height()) {
             canvas
         } else {
+            self.partial_redraw = false;
             tiny_skia::Pixmap::new(width, height).unwrap()
         };
-        self.canvas = Some(self.get_img(width, height, buffer, canvas))
+        self.canvas = Some(if self.can_draw_appended() {
+            self.draw_appended(buffer, canvas)
+        } else {
+            self.get_img(width, height, buffer, canvas)
+        })
+    }
+    #[cfg(feature = "tiny-skia")]
+    fn draw_appended<T>(&mut self, _buffer: &mut T, canvas: tiny_skia::Pixmap) -> tiny_skia::Pixmap
+    where
+        T: std::ops::DerefMut<Target = [u32]>,
+    {
+        let mut painter = Painter::retained(self.anti_alias, self.draw_offset, canvas);
+        self.plot_appended(&mut painter);
+        #[cfg(not(target_arch = "wasm32"))]
+        painter.save(_buffer);
+        painter.canvas
+    }
+    #[cfg(any(
+        feature = "tiny-skia",
+        all(feature = "skia", not(feature = "skia-vulkan"))
+    ))]
+    fn can_draw_appended(&mut self) -> bool {
+        if self.follow && !self.is_3d {
+            self.follow_newest();
+        }
+        self.partial_redraw
+            && !self.appended.is_empty()
+            && !self.is_3d
+            && self.graph_mode == GraphMode::Normal
+            && self.names.iter().all(|n| n.fill == Fill::None)
+            && self.drawn_view == (self.offset, self.zoom, self.bound)
+            && !self.appended_under_legend()
+    }
+    #[cfg(any(
+        feature = "tiny-skia",
+        all(feature = "skia", not(feature = "skia-vulkan"))
+    ))]
+    ///if the appended points reach the legend, which can not be drawn twice over itself
+    fn appended_under_legend(&mut self) -> bool {
+        let (entries, corner, size) = self.legend_layout();
+        if entries.is_empty() {
+            return false;
+        }
+        let (top, bottom) = if self.legend.pos == LegendPos::Outside {
+            (
+                Pos::new(corner.x, 0.0),
+                Pos::new(corner.x + size.x, self.screen.y as f32),
+            )
+        } else {
+            (corner, corner + size)
+        };
+        let margin = self.line_width.max(self.point_size);
+        let secondary = self.secondary_sets();
+        for (k, start) in self.appended.clone() {
+            let restore = secondary[k].then(|| self.enter_secondary());
+            let (mut min, mut max) = (
+                Pos::new(f32::INFINITY, f32::INFINITY),
+                Pos::new(f32::NEG_INFINITY, f32::NEG_INFINITY),
+            );
+            if let Some(GraphData::Coord(data)) = self.data.get(k)
+                && let Some(data) = data.get(start.saturating_sub(1)..)
+            {
+                for (x, z) in data {
+                    let (a, b) = z.to_options();
+                    for y in [a, b].into_iter().flatten() {
+                        let p = self.to_screen(*x, y);
+                        (min.x, min.y) = (min.x.min(p.x), min.y.min(p.y));
+                        (max.x, max.y) = (max.x.max(p.x), max.y.max(p.y));
+                    }
+                }
+            }
+            if let Some(restore) = restore {
+                (self.offset.y, self.zoom.y) = restore
+            }
+            if min.x - margin <= bottom.x
+                && max.x + margin >= top.x
+                && min.y - margin <= bottom.y
+                && max.y + margin >= top.y
+            {
+                return true;
+            }
+        }
+        false
+    }
+    #[cfg(any(
+        feature = "tiny-skia",
+        all(feature = "skia", not(feature = "skia-vulkan"))
+    ))]
+    fn plot_appended(&mut self, painter: &mut Painter) {
+        let mut cache = None;
+        let mut image_buffer = Vec::new();
+        let tex = |_: &mut Option<Image>, _: usize, _: usize, _: &mut Vec<u8>| {};
+        let secondary = self.secondary_sets();
+        for (k, start) in std::mem::take(&mut self.appended) {
+            let restore = secondary[k].then(|| self.enter_secondary());
+            if let Some(GraphData::Coord(data)) = self.data.get(k)
+                && let Some(data) = data.get(start.saturating_sub(1)..)
+            {
+                let data = GraphData::Coord(data.to_vec());
+                self.plot_type(
+                    painter,
+                    &tex,
+                    &mut None,
+                    k,
+                    &data,
+                    &mut cache,
+                    &mut image_buffer,
+                );
+            }
+            if let Some(restore) = restore {
+                (self.offset.y, self.zoom.y) = restore
+            }
+        }
     }
     #[cfg(feature = "tiny-skia")]
     fn get_img<T>(
@@ -624,10 +1047,37 @@ impl Graph {
         let plot = |painter: &mut Painter, graph: &mut Graph| graph.plot(painter);
         self.update_inner(&mut painter, plot, width as f64, height as f64);
     }
+    ///draws into the width by height rect at origin of a shared painter
+    pub(crate) fn draw_pane<F>(
+        &mut self,
+        painter: &mut Painter,
+        plot: F,
+        origin: Pos,
+        width: f64,
+        height: f64,
+    ) where
+        F: Fn(&mut Painter, &mut Graph) -> Option<Vec<(f32, Draw, Color)>>,
+    {
+        self.pane_offset = origin;
+        self.set_screen(width, height, true, true);
+        painter.offset = origin;
+        painter.fill_rect(
+            Pos::new(0.0, 0.0),
+            Pos::new(width as f32, height as f32),
+            &self.background_color,
+        );
+        painter.clip(Pos::new(0.0, 0.0), Pos::new(width as f32, height as f32));
+        painter.offset = self.draw_offset;
+        self.update_inner(painter, plot, width, height);
+        painter.unclip();
+    }
     fn update_inner<F>(&mut self, painter: &mut Painter, plot: F, width: f64, height: f64)
     where
         F: Fn(&mut Painter, &mut Graph) -> Option<Vec<(f32, Draw, Color)>>,
     {
+        if self.follow && !self.is_3d {
+            self.follow_newest();
+        }
         self.delta = if self.is_3d {
             self.screen.x.min(self.screen.y)
         } else {
@@ -645,6 +1095,8 @@ impl Graph {
                 plot(painter, self);
             }
             self.write_text(painter);
+            self.write_secondary_text(painter);
+            self.write_conformal_source(painter);
         } else {
             (self.sin_phi, self.cos_phi) = self.angle.x.sin_cos();
             (self.sin_theta, self.cos_theta) = self.angle.y.sin_cos();
@@ -666,7 +1118,29 @@ impl Graph {
                     }
                 }
             }
+            //picking walks every vertex, so only when the cursor or the view moved
+            let view = self.mouse_position.map(|p| {
+                (
+                    p,
+                    self.angle,
+                    self.offset3d,
+                    self.zoom_3d,
+                    self.box_size,
+                    self.screen,
+                )
+            });
+            if view != self.hover_view {
+                self.hover_view = view;
+                self.hover = self.mouse_position.and_then(|p| self.pick(p));
+            }
         }
+        self.write_branch_cuts(painter);
+        self.write_picks(painter);
+        self.write_gradient_bar(painter);
+        self.write_zeros_poles(painter);
+        self.write_domain_wheel(painter);
+        self.write_titles(painter);
+        self.write_annotations(painter);
         let draw = !matches!(self.menu, Menu::Normal);
         if !self.is_3d {
             self.write_coord(painter);
@@ -684,87 +1158,361 @@ impl Graph {
             self.write_side(painter);
             self.set_screen(width, height, true, false);
         }
+        self.appended.clear();
+        self.partial_redraw = true;
+        self.drawn_view = (self.offset, self.zoom, self.bound);
     }
-    fn write_label(&self, painter: &mut Painter) {
-        let mut pos = Pos::new(self.screen.x as f32 - 48.0, 0.0);
+    fn follow_newest(&mut self) {
+        let Some(x) = self
+            .data
+            .iter()
+            .filter_map(|d| match d {
+                GraphData::Coord(d) => d.last().map(|(x, _)| *x),
+                _ => None,
+            })
+            .max_by(|a, b| a.total_cmp(b))
+        else {
+            return;
+        };
+        let s = self.screen.x / (self.bound.y - self.bound.x);
+        let px = (x * s + self.screen_offset.x + self.offset.x) * self.zoom.x;
+        if (0.0..=self.screen.x).contains(&px) {
+            return;
+        }
+        let offset = self.screen.x / (2.0 * self.zoom.x) - x * s - self.screen_offset.x;
+        if offset.is_finite() && offset != self.offset.x {
+            self.offset.x = offset;
+            self.recalculate(None);
+        }
+    }
+    ///legend rows in drawing order, without positions
+    fn legend_entries(&self) -> Vec<LegendEntry> {
         let blacklist = self
             .blacklist_graphs
             .iter()
             .filter_map(|i| self.index_to_name(*i, true).0)
             .collect::<Vec<usize>>();
-        for (i, Name { name, show, .. }) in self.names.iter().enumerate().filter_map(|(i, n)| {
-            if !n.name.is_empty() && !blacklist.contains(&i) {
-                Some((i, n))
-            } else {
-                None
-            }
-        }) {
-            let y = (pos.y + 3.0 * self.font_size / 4.0).round();
-            let o = 3.5;
+        let mut entries = Vec::new();
+        for (data, (name, n)) in self
+            .names
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| !n.name.is_empty())
+            .enumerate()
+        {
+            let hidden = blacklist.contains(&name);
+            let main = self.main_colors[data % self.main_colors.len()];
+            let alt = self.alt_colors[data % self.alt_colors.len()];
+            let mut push = |label: String, color: Color| {
+                entries.push(LegendEntry {
+                    name,
+                    data,
+                    label,
+                    color,
+                    hidden,
+                    pos: Pos::default(),
+                })
+            };
             match self.graph_mode {
-                GraphMode::DomainColoring => {}
-                GraphMode::Flatten | GraphMode::Depth => {
-                    self.text_color(pos, Align::RightTop, name, painter);
-                    painter.line_segment(
-                        [
-                            Pos::new(pos.x + o, y),
-                            Pos::new(self.screen.x as f32 - o, y),
-                        ],
-                        self.line_width,
-                        &self.main_colors[i % self.main_colors.len()],
-                    );
-                }
+                GraphMode::DomainColoring | GraphMode::RiemannSphere => {}
+                GraphMode::Flatten
+                | GraphMode::Depth
+                | GraphMode::Implicit
+                | GraphMode::ConformalGrid => push(n.name.clone(), main),
                 GraphMode::SlicePolar | GraphMode::Polar | GraphMode::Normal | GraphMode::Slice => {
-                    match show {
-                        Show::Real => {
-                            self.text_color(pos, Align::RightTop, name, painter);
-                            painter.line_segment(
-                                [
-                                    Pos::new(pos.x + o, y),
-                                    Pos::new(self.screen.x as f32 - o, y),
-                                ],
-                                self.line_width,
-                                &self.main_colors[i % self.main_colors.len()],
-                            );
-                        }
-                        Show::Imag => {
-                            self.text_color(pos, Align::RightTop, &format!("im:{name}"), painter);
-                            painter.line_segment(
-                                [
-                                    Pos::new(pos.x + o, y),
-                                    Pos::new(self.screen.x as f32 - o, y),
-                                ],
-                                self.line_width,
-                                &self.alt_colors[i % self.alt_colors.len()],
-                            );
-                        }
+                    match n.show {
+                        Show::Real => push(n.name.clone(), main),
+                        Show::Imag => push(format!("im:{}", n.name), alt),
                         Show::Complex => {
-                            self.text_color(pos, Align::RightTop, &format!("re:{name}"), painter);
-                            painter.line_segment(
-                                [
-                                    Pos::new(pos.x + o, y),
-                                    Pos::new(self.screen.x as f32 - o, y),
-                                ],
-                                self.line_width,
-                                &self.main_colors[i % self.main_colors.len()],
-                            );
-                            pos.y += self.font_size;
-                            let y = y + self.font_size;
-                            self.text_color(pos, Align::RightTop, &format!("im:{name}"), painter);
-                            painter.line_segment(
-                                [
-                                    Pos::new(pos.x + o, y),
-                                    Pos::new(self.screen.x as f32 - o, y),
-                                ],
-                                self.line_width,
-                                &self.alt_colors[i % self.alt_colors.len()],
-                            );
+                            push(format!("re:{}", n.name), main);
+                            push(format!("im:{}", n.name), alt);
                         }
                         Show::None => {}
                     }
                 }
             }
-            pos.y += self.font_size;
+        }
+        entries
+    }
+    ///rows per column, width of a column and size of the legend box for these entries
+    fn legend_grid(&self, entries: &[LegendEntry], height: f32) -> (usize, f32, Pos) {
+        let (top, bottom) = self.title_margins();
+        let fit = ((height - top - bottom) / self.font_size).floor().max(1.0) as usize;
+        let rows = if self.legend.rows == 0 {
+            fit
+        } else {
+            self.legend.rows
+        }
+        .clamp(1, entries.len().max(1));
+        let cols = entries.len().div_ceil(rows);
+        let chars = entries
+            .iter()
+            .map(|e| e.label.chars().count())
+            .max()
+            .unwrap_or(0);
+        let width = chars as f32 * self.font_width + 48.0;
+        let size = Pos::new(
+            cols as f32 * width + 8.0,
+            rows as f32 * self.font_size + 8.0,
+        );
+        (rows, width, size)
+    }
+    ///positioned legend rows, the top left corner of the legend and its size
+    fn legend_layout(&self) -> (Vec<LegendEntry>, Pos, Pos) {
+        let mut entries = self.legend_entries();
+        if entries.is_empty() {
+            return (entries, Pos::default(), Pos::default());
+        }
+        let (sx, sy) = (self.screen.x as f32, self.screen.y as f32);
+        let (rows, width, size) = self.legend_grid(&entries, sy);
+        let (top, bottom) = self.title_margins();
+        let corner = match self.legend.pos {
+            LegendPos::TopRight => Pos::new(sx - size.x, top),
+            LegendPos::TopLeft => Pos::new(0.0, top),
+            LegendPos::BottomLeft => Pos::new(0.0, sy - bottom - size.y),
+            LegendPos::BottomRight => Pos::new(sx - size.x, sy - bottom - size.y),
+            LegendPos::Outside => Pos::new(sx, top),
+            LegendPos::Free(p) => Pos::new(
+                (p.x as f32 * sx).clamp(0.0, (sx - size.x).max(0.0)),
+                (p.y as f32 * sy).clamp(0.0, (sy - size.y).max(0.0)),
+            ),
+        };
+        for (n, e) in entries.iter_mut().enumerate() {
+            e.pos = Pos::new(
+                corner.x + 4.0 + (n / rows) as f32 * width,
+                corner.y + 4.0 + (n % rows) as f32 * self.font_size,
+            );
+        }
+        (entries, corner, size)
+    }
+    ///width taken from the plot by a legend outside of it
+    fn outside_legend_width(&self, height: f64) -> f64 {
+        if self.legend.pos != LegendPos::Outside {
+            return 0.0;
+        }
+        let entries = self.legend_entries();
+        if entries.is_empty() {
+            return 0.0;
+        }
+        self.legend_grid(&entries, height as f32).2.x as f64
+    }
+    ///the legend box under p, and the entry under p if any
+    fn legend_hit(&self, p: Pos) -> Option<(Pos, Option<LegendEntry>)> {
+        let (entries, corner, size) = self.legend_layout();
+        if entries.is_empty()
+            || p.x < corner.x
+            || p.y < corner.y
+            || p.x > corner.x + size.x
+            || p.y > corner.y + size.y
+        {
+            return None;
+        }
+        let (_, width, _) = self.legend_grid(&entries, self.screen.y as f32);
+        let entry = entries.into_iter().find(|e| {
+            p.x >= e.pos.x
+                && p.x < e.pos.x + width
+                && p.y >= e.pos.y
+                && p.y < e.pos.y + self.font_size
+        });
+        Some((corner, entry))
+    }
+    ///hover highlights, clicking toggles and dragging moves the legend,
+    ///true if the pointer is being used by the legend
+    fn legend_input(&mut self, i: &InputState) -> bool {
+        let mpos = self.mouse_position.map(|p| p.to_pos());
+        let hit = mpos
+            .filter(|_| i.pointer_pos.is_some())
+            .and_then(|p| self.legend_hit(p));
+        let hover = hit.as_ref().and_then(|(_, e)| e.as_ref().map(|e| e.data));
+        if hover != self.legend_hover {
+            self.legend_hover = hover;
+            self.partial_redraw = false;
+        }
+        match (i.pointer, self.legend_drag, mpos) {
+            (Some(true), _, Some(p)) => {
+                self.legend_drag = hit.map(|(corner, e)| {
+                    (
+                        Pos::new(p.x - corner.x, p.y - corner.y),
+                        p,
+                        e.map(|e| e.name),
+                    )
+                });
+                self.legend_drag.is_some()
+            }
+            (Some(false), Some((grab, press, _)), Some(p)) => {
+                if (p.x - press.x).hypot(p.y - press.y) > 4.0 {
+                    self.partial_redraw = false;
+                    let (sx, sy) = (self.screen.x as f32, self.screen.y as f32);
+                    self.legend.pos = LegendPos::Free(Vec2::new(
+                        ((p.x - grab.x) / sx) as f64,
+                        ((p.y - grab.y) / sy) as f64,
+                    ));
+                }
+                true
+            }
+            (None, Some((_, press, name)), _) => {
+                self.legend_drag = None;
+                if let (Some(name), Some(p)) = (name, mpos)
+                    && (p.x - press.x).hypot(p.y - press.y) <= 4.0
+                    && matches!(self.menu, Menu::Normal | Menu::Side)
+                {
+                    self.toggle_graph(self.name_to_index(name))
+                }
+                true
+            }
+            (_, Some(_), _) => true,
+            _ => false,
+        }
+    }
+    fn write_label(&self, painter: &mut Painter) {
+        let (entries, corner, size) = self.legend_layout();
+        if entries.is_empty() {
+            return;
+        }
+        if self.legend.pos == LegendPos::Outside {
+            painter.fill_rect(
+                Pos::new(corner.x, 0.0),
+                Pos::new(corner.x + size.x, self.screen.y as f32),
+                &self.background_color,
+            );
+        } else if self.legend.background {
+            painter.fill_rect(
+                corner,
+                corner + size,
+                &self.background_color.with_alpha(192),
+            );
+        }
+        let (_, width, _) = self.legend_grid(&entries, self.screen.y as f32);
+        for e in &entries {
+            let x = e.pos.x + width - 48.0;
+            if e.hidden {
+                self.text(
+                    Pos::new(x, e.pos.y),
+                    Align::RightTop,
+                    &e.label,
+                    &self.axis_color_light,
+                    painter,
+                );
+            } else {
+                self.text_color(Pos::new(x, e.pos.y), Align::RightTop, &e.label, painter);
+            }
+            self.legend_mark(painter, e, x + 3.5, e.pos.x + width - 3.5);
+        }
+    }
+    fn write_annotations(&self, painter: &mut Painter) {
+        for a in &self.annotations {
+            let color = a.color.as_ref().unwrap_or(&self.text_color);
+            let pos = self.data_to_screen(a.pos);
+            if let Some(target) = a.arrow {
+                let tip = self.data_to_screen(target);
+                let (dx, dy) = (pos.x - tip.x, pos.y - tip.y);
+                let len = dx.hypot(dy);
+                if len > 0.0 {
+                    let (dx, dy) = (dx / len, dy / len);
+                    let h = self.font_size / 2.0;
+                    let (s, c) = (std::f32::consts::FRAC_PI_6).sin_cos();
+                    let left =
+                        Pos::new(tip.x + h * (dx * c - dy * s), tip.y + h * (dy * c + dx * s));
+                    let right =
+                        Pos::new(tip.x + h * (dx * c + dy * s), tip.y + h * (dy * c - dx * s));
+                    painter.line_segment([pos, tip], 1.0, color);
+                    painter.line_segment([tip, left], 1.0, color);
+                    painter.line_segment([tip, right], 1.0, color);
+                }
+            }
+            self.text(pos, a.align, &a.text, color, painter);
+        }
+    }
+    ///data coordinates that put a point at p on screen, in 3d it is moved parallel to the screen
+    ///so it keeps its depth
+    fn screen_to_data(&self, v: Vec3, p: Pos) -> Vec3 {
+        if self.is_3d {
+            let q = self.data_to_screen(v);
+            let s = self.delta / self.box_size;
+            let x1 = (p.x - q.x) as f64 / s;
+            let z2 = (p.y - q.y) as f64 / s;
+            let (z, y1) = (-self.cos_theta * z2, -self.sin_theta * z2);
+            let x = x1 * self.cos_phi - y1 * self.sin_phi;
+            let y = x1 * self.sin_phi + y1 * self.cos_phi;
+            Vec3::new(
+                v.x + x / self.zoom_3d.x,
+                v.y + y / self.zoom_3d.y,
+                v.z + z / self.zoom_3d.z,
+            )
+        } else {
+            let (x, y) = self.to_coord(p);
+            Vec3::new(x, y, v.z)
+        }
+    }
+    ///screen position of a point in data coordinates, z is only used in 3d
+    fn data_to_screen(&self, p: Vec3) -> Pos {
+        if self.is_3d {
+            let p = Vec3::new(
+                p.x - self.offset3d.x,
+                p.y + self.offset3d.y,
+                p.z + self.offset3d.z,
+            );
+            self.vec3_to_pos_depth(p, true).0
+        } else {
+            self.to_screen(p.x, p.y)
+        }
+    }
+    fn legend_mark(&self, painter: &mut Painter, e: &LegendEntry, x: f32, end: f32) {
+        let y = (e.pos.y + 3.0 * self.font_size / 4.0).round();
+        let color = if e.hidden {
+            &self.axis_color_light
+        } else {
+            &e.color
+        };
+        if matches!(
+            self.data.get(e.data),
+            Some(GraphData::Bars(_) | GraphData::Histogram(_, _) | GraphData::Band(_))
+        ) {
+            let h = self.font_size / 4.0;
+            painter.fill_rect(Pos::new(x, y - h), Pos::new(end, y + h), color);
+        } else {
+            let style = self.names.get(e.name).map(|n| n.style).unwrap_or_default();
+            let lines = style.lines.unwrap_or(self.lines);
+            let width = style.line_width.unwrap_or(self.line_width);
+            let width = if self.legend_hover == Some(e.data) {
+                width * 2.0
+            } else {
+                width
+            };
+            let mid = ((x + end) * 0.5).round();
+            if !matches!(lines, Lines::Points) {
+                let h = self.font_size / 4.0;
+                let mut phase = 0.0;
+                let mut line = |a: Pos, b: Pos| {
+                    self.dashed_segment(
+                        painter,
+                        [a, b],
+                        width,
+                        color,
+                        style.dash,
+                        &mut phase,
+                        false,
+                    )
+                };
+                match style.connect {
+                    Connect::Line => line(Pos::new(x, y), Pos::new(end, y)),
+                    Connect::Stem => line(Pos::new(mid, y + h), Pos::new(mid, y - h)),
+                    Connect::Pre | Connect::Post | Connect::Mid => {
+                        let (a, b) = (Pos::new(mid, y + h), Pos::new(mid, y - h));
+                        line(Pos::new(x, y + h), a);
+                        line(a, b);
+                        line(b, Pos::new(end, y - h));
+                    }
+                }
+            }
+            if !matches!(lines, Lines::Lines) {
+                let y = if style.connect == Connect::Stem {
+                    y - self.font_size / 4.0
+                } else {
+                    y
+                };
+                self.draw_marker(painter, Pos::new(mid, y), style.marker, color)
+            }
         }
     }
     fn write_coord(&self, painter: &mut Painter) {
@@ -774,12 +1522,12 @@ impl Graph {
             let p = self.to_coord(pos.to_pos());
             if !self.disable_coord {
                 let s = if self.graph_mode == GraphMode::DomainColoring {
-                    if let GraphData::Width3D(data, sx, sy, ex, ey) = &self.data[0] {
-                        let len = data.len().isqrt();
-                        let i = ((p.0 - sx) / (ex - sx) * len as f64).round() as usize;
-                        let j = ((p.1 - sy) / (ey - sy) * len as f64).round() as usize;
-                        let ind = i + len * j;
-                        if ind < data.len() {
+                    if let GraphData::Width3D(data, sx, sy, ex, ey, len_x) = &self.data[0] {
+                        let (lenx, leny) = grid_size(data.len(), *len_x);
+                        let i = ((p.0 - sx) / (ex - sx) * lenx as f64).round() as usize;
+                        let j = ((p.1 - sy) / (ey - sy) * leny as f64).round() as usize;
+                        let ind = i + lenx * j;
+                        if lenx != 0 && ind < data.len() {
                             let (x, y) = data[ind].to_options();
                             let (x, y) = (x.unwrap_or(0.0), y.unwrap_or(0.0));
                             format!(
@@ -803,6 +1551,9 @@ impl Graph {
                         p.1.hypot(p.0),
                         self.angle_type.to_val(p.1.atan2(p.0))
                     )
+                } else if self.secondary_sets().contains(&true) {
+                    let y2 = p.1 * self.secondary_scale + self.secondary_offset;
+                    format!("{:E}\n{:E}\n{:E}", p.0, p.1, y2)
                 } else {
                     format!("{:E}\n{:E}", p.0, p.1)
                 };
@@ -814,13 +1565,27 @@ impl Graph {
                     painter,
                 );
             }
-            if let Some(ps) = self.ruler_pos {
-                let dx = p.0 - ps.x;
-                let dy = p.1 - ps.y;
+            if let Some((a, b, h, hist)) = self.get_bar(p.0, p.1) {
+                let s = if hist {
+                    format!("[{a:E},{b:E})\n{h}")
+                } else {
+                    format!("{:E}\n{:E}", (a + b) * 0.5, h)
+                };
                 self.text(
-                    self.screen.to_pos(),
-                    Align::RightBottom,
-                    &format!(
+                    pos.to_pos() + Pos::new(8.0, -8.0),
+                    Align::LeftBottom,
+                    &s,
+                    &self.text_color,
+                    painter,
+                );
+            }
+            if let Some(ps) = self.ruler_pos {
+                let dx = p.0 - ps.x;
+                let dy = p.1 - ps.y;
+                self.text(
+                    self.screen.to_pos(),
+                    Align::RightBottom,
+                    &format!(
                         "{:E}\n{:E}\n{:E}\n{}",
                         dx,
                         dy,
@@ -830,10 +1595,14 @@ impl Graph {
                     &self.text_color,
                     painter,
                 );
-                painter.line_segment(
+                self.dashed_segment(
+                    painter,
                     [pos.to_pos(), self.to_screen(ps.x, ps.y)],
                     1.0,
                     &self.axis_color,
+                    self.ruler_dash,
+                    &mut 0.0,
+                    true,
                 );
             }
         }
@@ -883,11 +1652,25 @@ impl Graph {
     }
     fn write_angle(&self, painter: &mut Painter) {
         if !self.disable_coord {
+            let picked = self.hover.map_or(String::new(), |h| {
+                let mut s = format!("x {:E}\ny {:E}\nz {:E}\n", h.x, h.y, h.z);
+                //real data reads its value off z, except as the radius in polar
+                if !matches!(h.value, Complex::Real(_)) || self.graph_mode == GraphMode::Polar {
+                    let (re, im) = h.value.to_options();
+                    if let Some(re) = re {
+                        s += &format!("re {re:E}\n")
+                    }
+                    if let Some(im) = im {
+                        s += &format!("im {im:E}\n")
+                    }
+                }
+                s
+            });
             self.text(
                 Pos::new(0.0, self.screen.y as f32),
                 Align::LeftBottom,
                 &format!(
-                    "{}\n{}",
+                    "{picked}{}\n{}",
                     (self.angle.x / TAU * 360.0).round(),
                     ((0.25 - self.angle.y / TAU) * 360.0)
                         .round()
@@ -922,32 +1705,736 @@ impl Graph {
         let y = o.y - self.screen_offset.y + self.screen.y / (self.zoom.y * 2.0);
         Vec2::new(x, y)
     }
+    ///draws a point and joins it to the last one, which carries the dash phase of the curve
     fn draw_point(
         &self,
         painter: &mut Painter,
         x: f64,
         y: f64,
         color: &Color,
-        last: Option<Pos>,
-    ) -> Option<Pos> {
+        last: Option<(Pos, f32)>,
+        style: &Style,
+    ) -> Option<(Pos, f32)> {
         if !x.is_finite() || !y.is_finite() {
             return None;
         }
         let pos = self.to_screen(x, y);
         let is_in = self.in_screen(pos);
-        if !matches!(self.lines, Lines::Lines) && is_in {
-            painter.rect_filled(pos, color, self.point_size);
+        let lines = style.lines.unwrap_or(self.lines);
+        let width = style.line_width.unwrap_or(self.line_width);
+        if !matches!(lines, Lines::Lines) && is_in {
+            self.draw_marker(painter, pos, style.marker, color);
         }
-        if !matches!(self.lines, Lines::Points) {
-            if let Some(last) = last
-                && (is_in || self.in_screen(last))
+        if matches!(lines, Lines::Points) {
+            return None;
+        }
+        let mut phase = 0.0;
+        if style.connect == Connect::Stem {
+            let base = self.to_screen(x, 0.0);
+            if is_in || self.in_screen(base) {
+                let pos = self.clamp_screen(pos);
+                self.dashed_segment(
+                    painter,
+                    [base, pos],
+                    width,
+                    color,
+                    style.dash,
+                    &mut phase,
+                    true,
+                );
+            }
+        } else if let Some((last, p)) = last {
+            phase = p;
+            let mut line = |a: Pos, b: Pos| {
+                self.dashed_segment(painter, [a, b], width, color, style.dash, &mut phase, true)
+            };
+            match style.connect {
+                Connect::Pre => {
+                    let corner = Pos::new(last.x, pos.y);
+                    line(last, corner);
+                    line(corner, pos);
+                }
+                Connect::Post => {
+                    let corner = Pos::new(pos.x, last.y);
+                    line(last, corner);
+                    line(corner, pos);
+                }
+                Connect::Mid => {
+                    let mid = (last.x + pos.x) * 0.5;
+                    let (a, b) = (Pos::new(mid, last.y), Pos::new(mid, pos.y));
+                    line(last, a);
+                    line(a, b);
+                    line(b, pos);
+                }
+                _ => line(last, pos),
+            }
+        }
+        Some((pos, phase))
+    }
+    ///draws a segment, skipping it if clip and it misses the screen,
+    ///advancing phase along the dash pattern either way
+    #[allow(clippy::too_many_arguments)]
+    fn dashed_segment(
+        &self,
+        painter: &mut Painter,
+        p: [Pos; 2],
+        width: f32,
+        color: &Color,
+        dash: Dash,
+        phase: &mut f32,
+        clip: bool,
+    ) {
+        let visible = !clip || self.in_screen(p[0]) || self.in_screen(p[1]);
+        let pattern = dash.pattern();
+        if pattern.is_empty() {
+            if visible {
+                painter.line_segment(p, width, color)
+            }
+            return;
+        }
+        let mut scaled = [0.0; 4];
+        let scaled = &mut scaled[..pattern.len()];
+        for (s, d) in scaled.iter_mut().zip(pattern) {
+            *s = d * width.max(1.0)
+        }
+        if visible {
+            painter.line_dashed(p, width, color, scaled, *phase)
+        }
+        let len = (p[1].x - p[0].x).hypot(p[1].y - p[0].y);
+        *phase = (*phase + len) % scaled.iter().sum::<f32>()
+    }
+    fn draw_marker(&self, painter: &mut Painter, pos: Pos, marker: Marker, color: &Color) {
+        let r = self.point_size * 0.5;
+        let c = Pos::new(pos.x + 0.5, pos.y + 0.5);
+        match marker {
+            Marker::Square => painter.rect_filled(pos, color, self.point_size),
+            Marker::Circle => {
+                let n = 8;
+                let (top, bottom): (Vec<Pos>, Vec<Pos>) = (0..=n)
+                    .map(|i| {
+                        let (s, t) = (PI * i as f64 / n as f64).sin_cos();
+                        let (x, y) = (c.x - r * t as f32, r * s as f32);
+                        (Pos::new(x, c.y - y), Pos::new(x, c.y + y))
+                    })
+                    .unzip();
+                painter.fill_between(&top, &bottom, color)
+            }
+            Marker::Cross => {
+                let w = (self.point_size / 3.0).max(1.0);
+                painter.line_segment(
+                    [
+                        Pos::new(pos.x - r, pos.y - r),
+                        Pos::new(pos.x + r, pos.y + r),
+                    ],
+                    w,
+                    color,
+                );
+                painter.line_segment(
+                    [
+                        Pos::new(pos.x - r, pos.y + r),
+                        Pos::new(pos.x + r, pos.y - r),
+                    ],
+                    w,
+                    color,
+                );
+            }
+            Marker::Triangle => painter.fill_between(
+                &[
+                    Pos::new(c.x - r, c.y + r),
+                    Pos::new(c.x, c.y - r),
+                    Pos::new(c.x + r, c.y + r),
+                ],
+                &[
+                    Pos::new(c.x - r, c.y + r),
+                    Pos::new(c.x, c.y + r),
+                    Pos::new(c.x + r, c.y + r),
+                ],
+                color,
+            ),
+            Marker::Diamond => painter.fill_between(
+                &[
+                    Pos::new(c.x - r, c.y),
+                    Pos::new(c.x, c.y - r),
+                    Pos::new(c.x + r, c.y),
+                ],
+                &[
+                    Pos::new(c.x - r, c.y),
+                    Pos::new(c.x, c.y + r),
+                    Pos::new(c.x + r, c.y),
+                ],
+                color,
+            ),
+        }
+    }
+    ///the style of the k-th data set
+    fn style(&self, k: usize) -> Style {
+        self.names
+            .iter()
+            .filter(|n| !n.name.is_empty())
+            .nth(k)
+            .map(|n| n.style)
+            .unwrap_or_default()
+    }
+    ///most bins a histogram is split into, past a few per pixel they are not visible
+    fn max_bins(&self) -> usize {
+        (4.0 * self.screen.x) as usize
+    }
+    ///bins every histogram data set whose samples or settings changed since the last frame
+    fn bin_data(&mut self) {
+        let (max, generation) = (self.max_bins(), self.generation);
+        let mut binned = std::mem::take(&mut self.binned);
+        binned.resize_with(self.data.len(), || None);
+        for (data, binned) in self.data.iter().zip(binned.iter_mut()) {
+            let GraphData::Histogram(data, bins) = data else {
+                *binned = None;
+                continue;
+            };
+            let len = data.len();
+            if binned.as_ref().is_some_and(|b| {
+                b.generation == generation && b.len == len && b.bins == *bins && b.max == max
+            }) {
+                continue;
+            }
+            let (start, width, counts) = bins.bin(data, max);
+            *binned = Some(Binned {
+                generation,
+                len,
+                bins: *bins,
+                max,
+                start,
+                width,
+                counts,
+            })
+        }
+        self.binned = binned;
+    }
+    ///bins of the k-th data set from the cache, or binned now if it is out of date
+    fn binned<'a>(
+        &'a self,
+        k: usize,
+        data: &[f64],
+        bins: Bins,
+    ) -> (f64, f64, std::borrow::Cow<'a, [usize]>) {
+        match self.binned.get(k).and_then(|b| b.as_ref()) {
+            Some(b)
+                if b.generation == self.generation
+                    && b.len == data.len()
+                    && b.bins == bins
+                    && b.max == self.max_bins() =>
             {
-                painter.line_segment([last, pos], self.line_width, color);
+                (b.start, b.width, (&b.counts[..]).into())
             }
-            Some(pos)
-        } else {
-            None
+            _ => {
+                let (start, width, counts) = bins.bin(data, self.max_bins());
+                (start, width, counts.into())
+            }
+        }
+    }
+    fn decimate_data(&mut self) {
+        if !self.decimate
+            || self.is_3d
+            || self.graph_mode != GraphMode::Normal
+            || !self
+                .names
+                .iter()
+                .any(|n| !n.name.is_empty() && n.style.plain(self.lines))
+        {
+            self.decimated.clear();
+            return;
+        }
+        let scale = self.screen.x / (self.bound.y - self.bound.x) * self.zoom.x;
+        let min_len = (4.0 * self.screen.x) as usize;
+        let (only_real, generation) = (self.only_real, self.generation);
+        let mut decimated = std::mem::take(&mut self.decimated);
+        decimated.resize_with(self.data.len(), || None);
+        let body = |(data, decimated): (&GraphData, &mut Option<(Decimation, GraphData)>)| {
+            let (len, start, end) = match data {
+                GraphData::Width(d, start, end) => (d.len(), *start, *end),
+                GraphData::Coord(d) => (d.len(), 0.0, 0.0),
+                _ => (0, 0.0, 0.0),
+            };
+            if len < min_len.max(2) {
+                *decimated = None;
+                return;
+            }
+            let key = Decimation {
+                generation,
+                len,
+                start,
+                end,
+                scale,
+                only_real,
+            };
+            if decimated.as_ref().is_some_and(|(k, _)| *k == key) {
+                return;
+            }
+            let data = match data {
+                GraphData::Width(d, start, end) => decimate(
+                    d.iter().enumerate().map(|(i, y)| {
+                        let x = (i as f64 / (len - 1) as f64 - 0.5) * (end - start)
+                            + (start + end) * 0.5;
+                        (x, *y)
+                    }),
+                    scale,
+                    only_real,
+                ),
+                GraphData::Coord(d) => decimate(d.iter().copied(), scale, only_real),
+                _ => unreachable!(),
+            };
+            *decimated = Some((key, data))
+        };
+        #[cfg(feature = "rayon")]
+        self.data
+            .par_iter()
+            .zip(decimated.par_iter_mut())
+            .for_each(body);
+        #[cfg(not(feature = "rayon"))]
+        self.data.iter().zip(decimated.iter_mut()).for_each(body);
+        self.decimated = decimated;
+    }
+    ///which data sets are drawn against the secondary y axis
+    fn secondary_sets(&self) -> Vec<bool> {
+        let active = self.graph_mode == GraphMode::Normal && !self.is_3d;
+        let mut secondary = self
+            .names
+            .iter()
+            .filter(|n| !n.name.is_empty())
+            .map(|n| active && n.secondary)
+            .collect::<Vec<bool>>();
+        secondary.resize(self.data.len(), false);
+        secondary
+    }
+    ///maps the primary y axis onto the secondary one, returning the y offset and zoom to restore
+    fn enter_secondary(&mut self) -> (f64, f64) {
+        let restore = (self.offset.y, self.zoom.y);
+        let s = self.screen.x / (self.bound.y - self.bound.x);
+        let oy = self.screen_offset.y + self.offset.y;
+        self.zoom.y /= self.secondary_scale;
+        self.offset.y =
+            oy * self.secondary_scale + self.secondary_offset * s - self.screen_offset.y;
+        restore
+    }
+    ///tick labels for the secondary y axis along the right edge
+    fn write_secondary_text(&self, painter: &mut Painter) {
+        if self.disable_axis || !self.secondary_sets().contains(&true) {
+            return;
+        }
+        let (top, bottom) = self.title_margins();
+        let (sx, sy) = (self.screen.x as f32, self.screen.y as f32);
+        let (a, b) = (self.secondary_scale, self.secondary_offset);
+        let deltay = 2.0f64.powf((-self.zoom.y.log2()).round());
+        let step = 2.0f64.powf(
+            (deltay * (self.bound.y - self.bound.x) / self.line_major as f64 * a.abs())
+                .log2()
+                .round(),
+        );
+        let v0 = self.to_coord(Pos::new(0.0, sy)).1 * a + b;
+        let v1 = self.to_coord(Pos::new(0.0, 0.0)).1 * a + b;
+        let (v0, v1) = (v0.min(v1), v0.max(v1));
+        for j in (v0 / step).ceil() as isize..=(v1 / step).floor() as isize {
+            let v = j as f64 * step;
+            let y = self.to_screen(0.0, (v - b) / a).y;
+            if y < top || y + self.font_size > sy - bottom {
+                continue;
+            }
+            let mut s = v.to_string();
+            if s.len() > 8 {
+                s = format!("{v:E}")
+            }
+            painter.line_segment(
+                [Pos::new(sx - 6.0, y), Pos::new(sx, y)],
+                1.0,
+                &self.axis_color,
+            );
+            self.text(
+                Pos::new(sx - 8.0, y),
+                Align::RightTop,
+                &s,
+                &self.text_color,
+                painter,
+            );
+        }
+    }
+    fn draw_fills(&self, painter: &mut Painter, secondary: &[bool], pass: bool) {
+        for (k, top, bottom) in self.fills(secondary, pass) {
+            let color = self.main_colors[k % self.main_colors.len()].with_alpha(self.fill_alpha);
+            painter.fill_between(&top, &bottom, &color);
+        }
+    }
+    ///the filled areas on screen as the data index and the top and bottom edges of each run,
+    ///a run ends where the data or what it is filled to is not finite
+    fn fills(&self, secondary: &[bool], pass: bool) -> Vec<(usize, Vec<Pos>, Vec<Pos>)> {
+        let mut fills = Vec::new();
+        if self.graph_mode != GraphMode::Normal || self.is_3d {
+            return fills;
+        }
+        let names = self
+            .names
+            .iter()
+            .filter(|n| !n.name.is_empty())
+            .collect::<Vec<&Name>>();
+        for (k, (name, data)) in names.iter().zip(self.data.iter()).enumerate() {
+            if name.fill == Fill::None || secondary[k] != pass {
+                continue;
+            }
+            let Some(samples) = data.real_samples() else {
+                continue;
+            };
+            let other = if let Fill::Between(other) = &name.fill {
+                let Some(mut other) = names
+                    .iter()
+                    .position(|n| &n.name == other)
+                    .and_then(|i| self.data.get(i))
+                    .and_then(|d| d.real_samples())
+                else {
+                    continue;
+                };
+                //coord data need not be ordered by x, interpolation needs it to be
+                if !other.is_sorted_by(|a, b| a.0 <= b.0) {
+                    other.sort_unstable_by(|a, b| a.0.total_cmp(&b.0))
+                }
+                other
+            } else {
+                Vec::new()
+            };
+            let mut top = Vec::with_capacity(samples.len());
+            let mut bottom = Vec::with_capacity(samples.len());
+            for (x, y) in samples {
+                let p = self.to_screen(x, y);
+                let q = match &name.fill {
+                    Fill::Axis => Some(self.to_screen(x, 0.0)),
+                    Fill::Above => Some(Pos::new(p.x, -2.0)),
+                    Fill::Below => Some(Pos::new(p.x, self.screen.y as f32 + 2.0)),
+                    Fill::Between(_) => interpolate(&other, x).map(|y| self.to_screen(x, y)),
+                    Fill::None => None,
+                };
+                if let Some(q) = q
+                    && x.is_finite()
+                    && y.is_finite()
+                {
+                    top.push(p);
+                    bottom.push(q);
+                } else if !top.is_empty() {
+                    let (t, b) = self.clip_between(&top, &bottom);
+                    fills.push((k, t, b));
+                    top.clear();
+                    bottom.clear();
+                }
+            }
+            if !top.is_empty() {
+                let (t, b) = self.clip_between(&top, &bottom);
+                fills.push((k, t, b));
+            }
+        }
+        fills
+    }
+    ///images of the horizontal lines in the main color then the vertical lines in the alt color
+    fn draw_conformal(&self, painter: &mut Painter, k: usize, lines: &[GraphData], style: &Style) {
+        let half = lines.len() / 2;
+        for (n, line) in lines.iter().enumerate() {
+            let color = if n < half {
+                &self.main_colors[k % self.main_colors.len()]
+            } else {
+                &self.alt_colors[k % self.alt_colors.len()]
+            };
+            let mut last = None;
+            let mut body = |z: &Complex| {
+                let (x, y) = z.to_options();
+                last = self.draw_point(
+                    painter,
+                    x.unwrap_or(0.0),
+                    y.unwrap_or(0.0),
+                    color,
+                    last,
+                    style,
+                )
+            };
+            match line {
+                GraphData::Width(data, _, _) => data.iter().for_each(&mut body),
+                GraphData::Coord(data) => data.iter().for_each(|(_, z)| body(z)),
+                _ => {}
+            }
+        }
+    }
+    fn has_conformal_source(&self) -> bool {
+        self.conformal_source && self.graph_mode == GraphMode::ConformalGrid && !self.is_3d
+    }
+    ///the requested grid in the input plane, right of the plot and any outside legend
+    fn write_conformal_source(&self, painter: &mut Painter) {
+        if !self.has_conformal_source() {
+            return;
         }
+        //the pane takes half the width unless that is taller than the plot
+        let w = self.screen.x.min(self.screen.y) as f32;
+        let x0 = (self.screen.x + self.outside_legend_width(self.screen.y)) as f32;
+        let y0 = (self.screen.y as f32 - w) * 0.5;
+        painter.fill_rect(
+            Pos::new(x0, 0.0),
+            Pos::new(x0 + w, self.screen.y as f32),
+            &self.background_color,
+        );
+        painter.line_segment(
+            [Pos::new(x0, 0.0), Pos::new(x0, self.screen.y as f32)],
+            1.0,
+            &self.axis_color,
+        );
+        let (start, end) = (self.var.x, self.var.y);
+        if !(end - start).is_normal() {
+            return;
+        }
+        let to = |t: f64| ((t - start) / (end - start)) as f32 * (w - 16.0) + 8.0;
+        if (start..=end).contains(&0.0) {
+            let o = to(0.0);
+            let color = &self.axis_color_light;
+            painter.line_segment([Pos::new(x0 + o, y0), Pos::new(x0 + o, y0 + w)], 1.0, color);
+            painter.line_segment(
+                [Pos::new(x0, y0 + w - o), Pos::new(x0 + w, y0 + w - o)],
+                1.0,
+                color,
+            );
+        }
+        let Some(k) = self.data.iter().position(|d| !matches!(d, GraphData::None)) else {
+            return;
+        };
+        let n = self.conformal_lines.max(2);
+        let (main, alt) = (
+            &self.main_colors[k % self.main_colors.len()],
+            &self.alt_colors[k % self.alt_colors.len()],
+        );
+        for i in 0..n {
+            let p = to(start + (end - start) * i as f64 / (n - 1) as f64);
+            painter.line_segment(
+                [
+                    Pos::new(x0 + 8.0, y0 + w - p),
+                    Pos::new(x0 + w - 8.0, y0 + w - p),
+                ],
+                self.line_width,
+                main,
+            );
+            painter.line_segment(
+                [Pos::new(x0 + p, y0 + 8.0), Pos::new(x0 + p, y0 + w - 8.0)],
+                self.line_width,
+                alt,
+            );
+        }
+    }
+    ///colors the unit sphere, scaled to the box, with bands along each latitude
+    ///of a longitude by latitude grid, hiding the far side
+    fn draw_sphere(
+        &self,
+        painter: &mut Painter,
+        buffer: &mut Option<Vec<(f32, Draw, Color)>>,
+        data: &[Complex],
+        (lenx, leny): (usize, usize),
+        (start_x, start_y, end_x, end_y): (f64, f64, f64, f64),
+    ) {
+        if lenx < 2 || leny < 2 || data.len() < lenx * leny {
+            return;
+        }
+        let (dx, dy) = (
+            (end_x - start_x) / (lenx - 1) as f64,
+            (end_y - start_y) / (leny - 1) as f64,
+        );
+        let r = (self.bound.y - self.bound.x) * 0.5;
+        let width = (r * dy.abs() * self.delta / self.box_size) as f32 * 1.5;
+        let project = |i: usize, j: usize| {
+            self.sphere_point(start_x + i as f64 * dx, start_y + j as f64 * dy)
+        };
+        for j in 0..leny {
+            let mut last = project(0, j);
+            for i in 1..lenx {
+                let cur = project(i, j);
+                if last.1 + cur.1 > 0.0 {
+                    let depth = last.0.1.zip(cur.0.1).map(|(a, b)| (a + b) * 0.5);
+                    line(
+                        buffer,
+                        Some(painter),
+                        depth,
+                        last.0.0,
+                        cur.0.0,
+                        self.domain_color(&data[i - 1 + lenx * j]),
+                        width,
+                    )
+                }
+                last = cur
+            }
+        }
+    }
+    ///where a longitude and latitude of the riemann sphere is drawn, and how far it faces
+    ///the viewer, positive in front
+    fn sphere_point(&self, lon: f64, lat: f64) -> ((Pos, Option<f32>), f64) {
+        let r = (self.bound.y - self.bound.x) * 0.5;
+        let (sl, cl) = lat.sin_cos();
+        let v = Vec3::new(r * cl * lon.cos(), r * cl * lon.sin(), r * sl);
+        let y1 = -v.x * self.sin_phi + v.y * self.cos_phi;
+        (
+            self.vec3_to_pos_depth(v, false),
+            v.z * self.sin_theta - y1 * self.cos_theta,
+        )
+    }
+    fn draw_implicit(
+        &self,
+        painter: &mut Painter,
+        data: &[Complex],
+        size: (usize, usize),
+        bounds: (f64, f64, f64, f64),
+        color: &Color,
+    ) {
+        for (a, b) in contour(data, size, bounds) {
+            let (a, b) = (self.to_screen(a.x, a.y), self.to_screen(b.x, b.y));
+            if self.in_screen(a) || self.in_screen(b) {
+                painter.line_segment([a, b], self.line_width, color)
+            }
+        }
+    }
+    fn clamp_screen(&self, p: Pos) -> Pos {
+        Pos::new(
+            p.x.clamp(-2.0, self.screen.x as f32 + 2.0),
+            p.y.clamp(-2.0, self.screen.y as f32 + 2.0),
+        )
+    }
+    fn draw_whiskers(
+        &self,
+        painter: &mut Painter,
+        x: f64,
+        y: f64,
+        ex: f64,
+        ey: f64,
+        color: &Color,
+    ) {
+        if !x.is_finite() || !y.is_finite() {
+            return;
+        }
+        let cap = self.point_size.max(2.0) * 1.5;
+        if ex.is_finite() && ex != 0.0 {
+            let a = self.to_screen(x - ex, y);
+            let b = self.to_screen(x + ex, y);
+            if self.in_screen(a) || self.in_screen(b) {
+                painter.line_segment([self.clamp_screen(a), self.clamp_screen(b)], 1.0, color);
+            }
+            for p in [a, b] {
+                if self.in_screen(p) {
+                    painter.line_segment(
+                        [Pos::new(p.x, p.y - cap), Pos::new(p.x, p.y + cap)],
+                        1.0,
+                        color,
+                    )
+                }
+            }
+        }
+        if ey.is_finite() && ey != 0.0 {
+            let a = self.to_screen(x, y - ey);
+            let b = self.to_screen(x, y + ey);
+            if self.in_screen(a) || self.in_screen(b) {
+                painter.line_segment([self.clamp_screen(a), self.clamp_screen(b)], 1.0, color);
+            }
+            for p in [a, b] {
+                if self.in_screen(p) {
+                    painter.line_segment(
+                        [Pos::new(p.x - cap, p.y), Pos::new(p.x + cap, p.y)],
+                        1.0,
+                        color,
+                    )
+                }
+            }
+        }
+    }
+    fn draw_band(&self, painter: &mut Painter, data: &[(f64, f64, f64)], color: &Color) {
+        let mut top = Vec::with_capacity(data.len());
+        let mut bottom = Vec::with_capacity(data.len());
+        for (x, lower, upper) in data {
+            if !x.is_finite() || !lower.is_finite() || !upper.is_finite() {
+                let (t, b) = self.clip_between(&top, &bottom);
+                painter.fill_between(&t, &b, color);
+                top.clear();
+                bottom.clear();
+                continue;
+            }
+            top.push(self.to_screen(*x, *upper));
+            bottom.push(self.to_screen(*x, *lower));
+        }
+        let (top, bottom) = self.clip_between(&top, &bottom);
+        painter.fill_between(&top, &bottom, color);
+    }
+    ///clips the region between two polylines which share x positions to the screen
+    fn clip_between(&self, top: &[Pos], bottom: &[Pos]) -> (Vec<Pos>, Vec<Pos>) {
+        let (w, h) = (self.screen.x as f32 + 2.0, self.screen.y as f32 + 2.0);
+        let mut t = Vec::with_capacity(top.len());
+        let mut b = Vec::with_capacity(bottom.len());
+        for (i, (p, q)) in top.iter().zip(bottom).enumerate() {
+            if i > 0 {
+                let (p0, q0) = (top[i - 1], bottom[i - 1]);
+                let mut cuts = Vec::new();
+                for (a, b, max) in [(p0.y, p.y, h), (q0.y, q.y, h), (p0.x, p.x, w)] {
+                    for edge in [-2.0, max] {
+                        let s = (edge - a) / (b - a);
+                        if s > 0.0 && s < 1.0 {
+                            cuts.push(s)
+                        }
+                    }
+                }
+                cuts.sort_by(|a, b| a.total_cmp(b));
+                for s in cuts {
+                    t.push(p0 + (*p - p0) * s);
+                    b.push(q0 + (*q - q0) * s);
+                }
+            }
+            t.push(*p);
+            b.push(*q);
+        }
+        t.into_iter()
+            .zip(b)
+            .filter(|(p, _)| p.x > -2.5 && p.x < w + 0.5)
+            .map(|(p, q)| (self.clamp_screen(p), self.clamp_screen(q)))
+            .unzip()
+    }
+    fn draw_bar(&self, painter: &mut Painter, start: f64, end: f64, height: f64, color: &Color) {
+        if !start.is_finite() || !end.is_finite() || !height.is_finite() {
+            return;
+        }
+        let a = self.to_screen(start, 0.0);
+        let b = self.to_screen(end, height);
+        if a.x.max(b.x) < 0.0 || a.x.min(b.x) > self.screen.x as f32 {
+            return;
+        }
+        let (a, b) = (self.clamp_screen(a), self.clamp_screen(b));
+        painter.fill_rect(a, b, color);
+        for p in [
+            [a, Pos::new(a.x, b.y)],
+            [Pos::new(a.x, b.y), b],
+            [b, Pos::new(b.x, a.y)],
+        ] {
+            painter.line_segment(p, 1.0, &self.background_color);
+        }
+    }
+    ///returns the bar or histogram bin under the given coordinate as (start, end, height, is_histogram)
+    fn get_bar(&self, x: f64, y: f64) -> Option<(f64, f64, f64, bool)> {
+        if self.is_3d || self.graph_mode != GraphMode::Normal {
+            return None;
+        }
+        let hit = |a: f64, b: f64, h: f64| x >= a && x < b && y >= h.min(0.0) && y <= h.max(0.0);
+        self.data
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(k, data)| match data {
+                GraphData::Bars(data) => data.iter().rev().find_map(|(c, h, w)| {
+                    let (a, b) = (c - w * 0.5, c + w * 0.5);
+                    hit(a, b, *h).then_some((a, b, *h, false))
+                }),
+                GraphData::Histogram(data, bins) => {
+                    let (start, width, bins) = self.binned(k, data, *bins);
+                    let i = ((x - start) / width).floor();
+                    if i >= 0.0 && (i as usize) < bins.len() {
+                        let a = start + i * width;
+                        let n = bins[i as usize] as f64;
+                        hit(a, a + width, n).then_some((a, a + width, n, true))
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            })
     }
     fn in_screen(&self, p: Pos) -> bool {
         p.x > -2.0
@@ -1110,6 +2597,7 @@ impl Graph {
         let ny = (((oy + 1.0 / self.zoom.y) / s) * 2.0 * minory).ceil() as isize;
         let my = (((oy - (self.screen.y + 1.0) / self.zoom.y) / s) * 2.0 * minory).floor() as isize;
         if !self.disable_axis {
+            let (top, bottom) = self.title_margins();
             let mut align = false;
             let y = if (my..ny).contains(&0) {
                 self.to_screen(0.0, 0.0).y
@@ -1117,7 +2605,7 @@ impl Graph {
                 0.0
             } else {
                 align = true;
-                self.screen.y as f32
+                self.screen.y as f32 - bottom
             };
             for j in nx.saturating_sub(1)..=mx {
                 if self.is_polar() && j == 0 {
@@ -1127,7 +2615,9 @@ impl Graph {
                 let x = self.to_screen(j, 0.0).x;
                 let mut p = Pos::new(x + 2.0, y);
                 if !align {
-                    p.y = p.y.min(self.screen.y as f32 - self.font_size)
+                    p.y =
+                        p.y.min(self.screen.y as f32 - self.font_size - bottom)
+                            .max(top)
                 }
                 let mut s = j.to_string();
                 if s.len() > 8 {
@@ -1160,6 +2650,9 @@ impl Graph {
                 }
                 let j = j as f64 / (2.0 * minory);
                 let y = self.to_screen(0.0, j).y;
+                if y < top || y + self.font_size > self.screen.y as f32 - bottom {
+                    continue;
+                }
                 let mut p = Pos::new(x + 2.0, y);
                 let mut s = j.to_string();
                 if s.len() > 8 {
@@ -1183,6 +2676,53 @@ impl Graph {
             }
         }
     }
+    ///space taken by titles at the top and bottom of the screen
+    fn title_margins(&self) -> (f32, f32) {
+        let rows = |s: &str| s.lines().count() as f32 * self.font_size;
+        if self.is_3d {
+            (rows(&self.title), 0.0)
+        } else {
+            (rows(&self.title) + rows(&self.y_title), rows(&self.x_title))
+        }
+    }
+    fn write_titles(&self, painter: &mut Painter) {
+        let title = self.title.lines().count() as f32 * self.font_size;
+        if !self.title.is_empty() {
+            let p = Pos::new(self.screen.x as f32 * 0.5, 0.0);
+            self.text(p, Align::CenterTop, &self.title, &self.text_color, painter);
+        }
+        if self.is_3d {
+            return;
+        }
+        if !self.x_title.is_empty() {
+            let p = Pos::new(self.screen.x as f32 * 0.5, self.screen.y as f32);
+            self.text(
+                p,
+                Align::CenterBottom,
+                &self.x_title,
+                &self.text_color,
+                painter,
+            );
+        }
+        if !self.y_title.is_empty() {
+            let width = self
+                .y_title
+                .lines()
+                .map(|l| l.chars().count())
+                .max()
+                .unwrap_or(0) as f32
+                * self.font_width;
+            let x = (self.to_screen(0.0, 0.0).x + 2.0)
+                .clamp(0.0, (self.screen.x as f32 - width).max(0.0));
+            self.text(
+                Pos::new(x, title),
+                Align::LeftTop,
+                &self.y_title,
+                &self.text_color,
+                painter,
+            );
+        }
+    }
     fn is_polar(&self) -> bool {
         matches!(self.graph_mode, GraphMode::Polar | GraphMode::SlicePolar)
     }
@@ -1238,13 +2778,39 @@ impl Graph {
         let y = (z2 * s + self.screen.y * 0.5) as f32;
         (
             Pos::new(x, y),
-            (!self.fast_3d()).then(|| {
-                ((p.z * self.sin_theta - y1 * self.cos_theta)
-                    / ((self.bound.y - self.bound.x) * 3.0f64.sqrt())
-                    + 0.5) as f32
-            }),
+            (!self.fast_3d()).then(|| self.view_depth(p) as f32),
         )
     }
+    ///how near a zoomed point is to the viewer, 0 to 1 across the box, later is in front
+    fn view_depth(&self, p: Vec3) -> f64 {
+        let y1 = -p.x * self.sin_phi + p.y * self.cos_phi;
+        (p.z * self.sin_theta - y1 * self.cos_theta)
+            / ((self.bound.y - self.bound.x) * 3.0f64.sqrt())
+            + 0.5
+    }
+    fn inside_3d(&self, v: Vec3) -> bool {
+        self.ignore_bounds
+            || (v.x >= self.bound.x / self.zoom_3d.x
+                && v.x <= self.bound.y / self.zoom_3d.x
+                && v.y >= self.bound.x / self.zoom_3d.y
+                && v.y <= self.bound.y / self.zoom_3d.y
+                && v.z >= self.bound.x / self.zoom_3d.z
+                && v.z <= self.bound.y / self.zoom_3d.z)
+    }
+    ///screen position and depth of a point drawn in 3d, none if the box clips it
+    fn project_3d(&self, x: f64, y: f64, z: f64) -> Option<(Pos, f64)> {
+        let v = Vec3::new(
+            x - self.offset3d.x,
+            y + self.offset3d.y,
+            z + self.offset3d.z,
+        );
+        if !v.x.is_finite() || !v.y.is_finite() || !v.z.is_finite() || !self.inside_3d(v) {
+            return None;
+        }
+        let mut zoomed = v;
+        zoomed *= self.zoom_3d;
+        Some((self.vec3_to_pos_depth(v, true).0, self.view_depth(zoomed)))
+    }
     #[allow(clippy::type_complexity)]
     #[allow(clippy::too_many_arguments)]
     fn draw_point_3d(
@@ -1266,13 +2832,7 @@ impl Graph {
         }
         let v = Vec3::new(x, y, z);
         let pos = self.vec3_to_pos_depth(v, true);
-        let inside = self.ignore_bounds
-            || (x >= self.bound.x / self.zoom_3d.x
-                && x <= self.bound.y / self.zoom_3d.x
-                && y >= self.bound.x / self.zoom_3d.y
-                && y <= self.bound.y / self.zoom_3d.y
-                && z >= self.bound.x / self.zoom_3d.z
-                && z <= self.bound.y / self.zoom_3d.z);
+        let inside = self.inside_3d(v);
         if !matches!(self.lines, Lines::Lines) && inside {
             point(
                 buffer,
@@ -1486,9 +3046,11 @@ impl Graph {
                         p * 0.5,
                         align,
                         &match s {
-                            Axis::Z => format!("z{}", " ".repeat(n.len())),
-                            Axis::X => " \nx".to_string(),
-                            Axis::Y => " \ny".to_string(),
+                            Axis::Z => {
+                                format!("{}{}", axis_name("z", &self.z_title), " ".repeat(n.len()))
+                            }
+                            Axis::X => format!(" \n{}", axis_name("x", &self.x_title)),
+                            Axis::Y => format!(" \n{}", axis_name("y", &self.y_title)),
                         },
                         &self.text_color,
                         painter,
@@ -1527,6 +3089,9 @@ impl Graph {
     ///expected to run before update_res()
     pub fn keybinds(&mut self, ui: &mut egui::Ui) {
         ui.input(|i| self.keybinds_inner(&i.into()));
+        if self.is_playing() {
+            ui.ctx().request_repaint()
+        }
         let s = self.clipboard.as_ref().unwrap().0.clone();
         if !s.is_empty() {
             ui.ctx().copy_text(s)
@@ -1550,10 +3115,20 @@ impl Graph {
         }
     }
     fn keybinds_inner(&mut self, i: &InputState) {
+        if !i.keys_pressed.is_empty()
+            || i.raw_scroll_delta != Vec2::splat(0.0)
+            || i.pointer.is_some()
+            || i.pointer_right.is_some()
+            || i.multi.is_some()
+            || i.pointer_pos.map(|p| p - self.draw_offset.to_vec()) != self.mouse_position
+        {
+            self.partial_redraw = false;
+        }
         #[cfg(any(feature = "skia", feature = "tiny-skia", feature = "wasm-draw"))]
         {
             self.request_redraw = false;
         }
+        self.tick(i);
         let Some(keybinds) = std::mem::take(&mut self.keybinds) else {
             unreachable!()
         };
@@ -1589,7 +3164,27 @@ impl Graph {
             if let Some(right) = i.pointer_right
                 && matches!(self.menu, Menu::Side | Menu::Normal)
             {
-                if right && mpos.x > 0.0 {
+                let near = (right && mpos.x > 0.0)
+                    .then(|| {
+                        self.annotations.iter().enumerate().find_map(|(n, a)| {
+                            let p = self.data_to_screen(a.pos);
+                            let grab = Pos::new(mpos.x as f32 - p.x, mpos.y as f32 - p.y);
+                            (grab.x * grab.x + grab.y * grab.y <= 32.0 * 32.0).then_some((n, grab))
+                        })
+                    })
+                    .flatten();
+                if near.is_some() {
+                    self.annotation_drag = near
+                } else if let Some((n, grab)) = self.annotation_drag {
+                    let p = Pos::new(mpos.x as f32 - grab.x, mpos.y as f32 - grab.y);
+                    if let Some(pos) = self
+                        .annotations
+                        .get(n)
+                        .map(|a| self.screen_to_data(a.pos, p))
+                    {
+                        self.annotations[n].pos = pos
+                    }
+                } else if right && mpos.x > 0.0 {
                     let get_d = |p: &Dragable| -> f32 {
                         match p {
                             Dragable::Point(p) | Dragable::Points((_, p)) => {
@@ -1720,7 +3315,8 @@ impl Graph {
                     self.side_drag = None
                 }
             } else {
-                self.side_drag = None
+                self.side_drag = None;
+                self.annotation_drag = None
             }
         }
         if i.keys_pressed(keybinds.toggle_dark_mode) {
@@ -1792,7 +3388,7 @@ impl Graph {
             self.clipboard
                 .as_mut()
                 .unwrap()
-                .set_text(&format!("{l}@{s}"));
+                .set_text(&format!("{l}@{}.{s}", legacy::TINY_VERSION));
         }
         #[cfg(feature = "serde")]
         if i.keys_pressed(keybinds.full_save) {
@@ -1831,7 +3427,9 @@ impl Graph {
             self.keybinds = Some(keybinds);
             return;
         }
+        let legend = self.legend_input(i);
         match &i.multi {
+            _ if legend => {}
             Some(multi) => {
                 self.last_multi = true;
                 match multi.zoom_delta.total_cmp(&1.0) {
@@ -1922,13 +3520,46 @@ impl Graph {
         }
         self.last_interact = i.pointer_pos;
         if ret {
+            self.keybinds_line(i, &keybinds);
             self.keybinds = Some(keybinds);
             return;
         }
-        let (ax, ay, b, c) = (
-            self.delta
-                / if self.zoom.x > 1.0 {
-                    2.0 * self.zoom.x
+        if self.is_3d {
+            //a click that does not rotate the view pins the vertex under it
+            match i.pointer {
+                Some(true) => self.pin_press = self.mouse_position,
+                Some(false) => {}
+                None => {
+                    if let Some(press) = self.pin_press.take()
+                        && self.mouse_position.is_some_and(|p| {
+                            (p.x - press.x).abs() < 2.0 && (p.y - press.y).abs() < 2.0
+                        })
+                    {
+                        self.toggle_pin()
+                    }
+                }
+            }
+        }
+        if i.keys_pressed(keybinds.play) {
+            self.toggle_play()
+        }
+        if i.keys_pressed(keybinds.step_forward) {
+            self.pause();
+            self.step_animation(true)
+        }
+        if i.keys_pressed(keybinds.step_back) {
+            self.pause();
+            self.step_animation(false)
+        }
+        if i.keys_pressed(keybinds.playback)
+            && let Some(a) = self.animation.as_mut()
+        {
+            a.playback = a.playback.cycle()
+        }
+        let (ax, ay, b, c) = (
+            self.delta
+                / if self.zoom.x > 1.0 {
+                    2.0 * self.zoom.x
                 } else {
                     1.0
                 },
@@ -1961,7 +3592,10 @@ impl Graph {
             if self.is_3d {
                 self.angle.y = ((self.angle.y / b - 1.0).round() * b).rem_euclid(TAU);
             } else {
-                if self.graph_mode == GraphMode::DomainColoring {
+                if matches!(
+                    self.graph_mode,
+                    GraphMode::DomainColoring | GraphMode::Implicit
+                ) {
                     self.recalculate(None);
                 }
                 self.offset.y += ay;
@@ -1971,7 +3605,10 @@ impl Graph {
             if self.is_3d {
                 self.angle.y = ((self.angle.y / b + 1.0).round() * b).rem_euclid(TAU);
             } else {
-                if self.graph_mode == GraphMode::DomainColoring {
+                if matches!(
+                    self.graph_mode,
+                    GraphMode::DomainColoring | GraphMode::Implicit
+                ) {
                     self.recalculate(None);
                 }
                 self.offset.y -= ay;
@@ -2065,10 +3702,23 @@ impl Graph {
             self.angle.y = (self.angle.y + i.raw_scroll_delta.y / 512.0).rem_euclid(TAU);
         } else {
             let rt = (i.raw_scroll_delta.y / 512.0).exp();
+            let rt = if i.modifiers.alt && rt != 1.0 && self.secondary_sets().contains(&true) {
+                let y = self.mouse_position.map_or(self.screen.y * 0.5, |p| p.y);
+                let p = self.to_coord(Pos::new(0.0, y as f32)).1;
+                let v = p * self.secondary_scale + self.secondary_offset;
+                self.secondary_scale /= rt;
+                self.secondary_offset = v - p * self.secondary_scale;
+                1.0
+            } else {
+                rt
+            };
             if i.keys_pressed(keybinds.domain_alternate) {
                 self.cache = None;
                 self.domain_alternate = !self.domain_alternate
             }
+            if i.keys_pressed(keybinds.zeros_poles) {
+                self.show_zeros_poles = !self.show_zeros_poles
+            }
             let (x, y) = (i.modifiers.ctrl, i.modifiers.shift);
             let a = !(x ^ y);
             match rt.total_cmp(&1.0) {
@@ -2223,17 +3873,40 @@ impl Graph {
         {
             if i.keys_pressed(keybinds.slice_up) {
                 self.recalculate(None);
+                self.slice_moved = true;
                 self.slice += c
             }
             if i.keys_pressed(keybinds.slice_down) {
                 self.recalculate(None);
+                self.slice_moved = true;
                 self.slice -= c
             }
+            if i.keys_pressed(keybinds.slice_play) {
+                self.slice_playing = !self.slice_playing;
+                self.slice_acc = 0.0
+            }
+            if i.keys_pressed(keybinds.onion_skin) {
+                self.onion_skin = if self.onion_skin == 0 { 3 } else { 0 };
+                self.ghosts.truncate(self.onion_skin)
+            }
             if i.keys_pressed(keybinds.slice_view) {
                 self.recalculate(None);
                 self.view_x = !self.view_x
             }
         }
+        if i.keys_pressed(keybinds.domain_scheme) {
+            self.cache = None;
+            self.domain_scheme = self.domain_scheme.cycle()
+        }
+        if i.keys_pressed(keybinds.domain_wheel) {
+            self.domain_wheel = !self.domain_wheel
+        }
+        if i.keys_pressed(keybinds.branch_cuts) {
+            self.show_branch_cuts = !self.show_branch_cuts
+        }
+        if i.keys_pressed(keybinds.gradient) {
+            self.gradient = self.gradient.cycle()
+        }
         if self.graph_mode == GraphMode::DomainColoring && i.keys_pressed(keybinds.log_scale) {
             self.cache = None;
             self.log_scale = !self.log_scale
@@ -2307,6 +3980,9 @@ impl Graph {
                 GraphMode::Flatten,
                 GraphMode::Depth,
                 GraphMode::DomainColoring,
+                GraphMode::Implicit,
+                GraphMode::RiemannSphere,
+                GraphMode::ConformalGrid,
             ],
             (true, false) => vec![
                 GraphMode::Normal,
@@ -2319,6 +3995,7 @@ impl Graph {
                 GraphMode::Polar,
                 GraphMode::Slice,
                 GraphMode::SlicePolar,
+                GraphMode::Implicit,
             ],
             (false, false) => vec![GraphMode::Normal, GraphMode::Polar],
         };
@@ -2365,7 +4042,11 @@ impl Graph {
         self.offset = offset;
         let l = seri.len();
         let comp = zstd::bulk::compress(&seri, 22).unwrap();
-        let s = base64::prelude::BASE64_URL_SAFE_NO_PAD.encode(&comp);
+        let s = format!(
+            "{}.{}",
+            legacy::SAVE_VERSION,
+            base64::prelude::BASE64_URL_SAFE_NO_PAD.encode(&comp)
+        );
         let l = base64::prelude::BASE64_URL_SAFE_NO_PAD.encode(l.to_string());
         let n = base64::prelude::BASE64_URL_SAFE_NO_PAD.encode(
             self.names
@@ -2439,19 +4120,19 @@ impl Graph {
         );
     }
     #[cfg(feature = "serde")]
+    ///loads the jth save, a save that can not be decoded is left alone
     pub(crate) fn load(&mut self, j: usize) {
         if Some(j) == self.save_num {
             return;
         }
         self.save();
         let fd = self.file_data.as_ref().unwrap();
-        if fd.is_empty() {
+        let Some((_, n, s)) = fd.get(j) else {
             return;
-        }
-        let (_, n, s) = &fd[j];
-        let s = base64::prelude::BASE64_URL_SAFE_NO_PAD.decode(s).unwrap();
-        let data = zstd::bulk::decompress(&s, *n).unwrap();
-        let mut graph: Graph = bitcode::deserialize(&data).unwrap();
+        };
+        let Some(mut graph) = legacy::decode_save(s, *n) else {
+            return;
+        };
         graph.save_num = Some(j);
         graph.file_data = std::mem::take(&mut self.file_data);
         graph.file_data_raw = std::mem::take(&mut self.file_data_raw);
@@ -2555,10 +4236,14 @@ impl Graph {
                     GraphData::Coord(_) => 0,
                     GraphData::Coord3D(d) => d.len(),
                     GraphData::Width(_, _, _) => 0,
-                    GraphData::Width3D(d, _, _, _, _) => d.len(),
+                    GraphData::Width3D(d, _, _, _, _, _) => d.len(),
                     GraphData::Constant(_, _) => 0,
                     GraphData::Point(_) => 0,
                     GraphData::List(a) => a.iter().map(su).sum(),
+                    GraphData::Bars(_) => 0,
+                    GraphData::Histogram(_, _) => 0,
+                    GraphData::CoordErr(_) => 0,
+                    GraphData::Band(_) => 0,
                     GraphData::None => 0,
                 }
             }
@@ -2575,23 +4260,106 @@ impl Graph {
                 };
             Vec::with_capacity(n + 12)
         });
+        if self.show_zeros_poles
+            && self.graph_mode == GraphMode::DomainColoring
+            && (self.cache.is_none() || self.zero_pole_cache.is_none())
+        {
+            self.zero_pole_cache = Some(self.zeros_poles())
+        } else if !self.show_zeros_poles {
+            self.zero_pole_cache = None
+        }
+        self.gradient_range = self.get_gradient_range();
+        if self.show_branch_cuts
+            && matches!(
+                self.graph_mode,
+                GraphMode::DomainColoring | GraphMode::RiemannSphere
+            )
+            && (self.cache.is_none() || self.branch_cut_cache.is_none())
+        {
+            self.branch_cut_cache = Some(self.branch_cuts())
+        } else if !self.show_branch_cuts {
+            self.branch_cut_cache = None
+        }
+        let secondary = self.secondary_sets();
+        let passes = if secondary.contains(&true) { 2 } else { 1 };
+        for pass in [false, true].into_iter().take(passes) {
+            let restore = pass.then(|| self.enter_secondary());
+            self.draw_fills(painter, &secondary, pass);
+            self.draw_ghosts(painter, &tex, &mut buffer, &secondary, pass);
+            if let Some(restore) = restore {
+                (self.offset.y, self.zoom.y) = restore
+            }
+        }
+        self.bin_data();
+        self.decimate_data();
         let mut cache = std::mem::take(&mut self.cache);
         let mut image_buffer = std::mem::take(&mut self.image_buffer);
-        for (k, data) in self.data.iter().enumerate() {
-            self.plot_type(
-                painter,
-                &tex,
-                &mut buffer,
-                k,
-                data,
-                &mut cache,
-                &mut image_buffer,
-            );
+        for pass in [false, true].into_iter().take(passes) {
+            let restore = pass.then(|| self.enter_secondary());
+            for (k, data) in self.data.iter().enumerate() {
+                if secondary[k] != pass {
+                    continue;
+                }
+                let data = self
+                    .decimated
+                    .get(k)
+                    .and_then(|d| d.as_ref())
+                    .filter(|_| self.style(k).plain(self.lines))
+                    .map_or(data, |(_, d)| d);
+                self.plot_type(
+                    painter,
+                    &tex,
+                    &mut buffer,
+                    k,
+                    data,
+                    &mut cache,
+                    &mut image_buffer,
+                );
+            }
+            if let Some(restore) = restore {
+                (self.offset.y, self.zoom.y) = restore
+            }
         }
         self.cache = cache;
         self.image_buffer = image_buffer;
         buffer
     }
+    ///draws the onion skin, older slices fainter, in plain colors so gradients stay readable
+    fn draw_ghosts<G>(
+        &mut self,
+        painter: &mut Painter,
+        tex: &G,
+        buffer: &mut Option<Vec<(f32, Draw, Color)>>,
+        secondary: &[bool],
+        pass: bool,
+    ) where
+        G: Fn(&mut Option<Image>, usize, usize, &mut Vec<u8>),
+    {
+        if self.ghosts.is_empty() {
+            return;
+        }
+        let ghosts = std::mem::take(&mut self.ghosts);
+        //ghosts share indices with the live data, so they bin for themselves
+        let binned = std::mem::take(&mut self.binned);
+        let (main, alt) = (self.main_colors.clone(), self.alt_colors.clone());
+        let range = self.gradient_range.take();
+        let n = ghosts.len();
+        for (age, ghost) in ghosts.iter().enumerate().rev() {
+            let alpha = (160 * (n - age) / (n + 1)) as u8;
+            self.main_colors = main.iter().map(|c| c.with_alpha(alpha)).collect();
+            self.alt_colors = alt.iter().map(|c| c.with_alpha(alpha)).collect();
+            for (k, data) in ghost.iter().enumerate() {
+                if secondary.get(k).copied().unwrap_or(false) != pass {
+                    continue;
+                }
+                self.plot_type(painter, tex, buffer, k, data, &mut None, &mut Vec::new());
+            }
+        }
+        (self.main_colors, self.alt_colors) = (main, alt);
+        self.gradient_range = range;
+        self.binned = binned;
+        self.ghosts = ghosts;
+    }
     #[allow(clippy::too_many_arguments)]
     fn plot_type<G>(
         &self,
@@ -2606,13 +4374,99 @@ impl Graph {
         G: Fn(&mut Option<Image>, usize, usize, &mut Vec<u8>),
     {
         let (mut a, mut b, mut c) = (None, None, None);
+        let mut style = self.style(k);
+        if self.legend_hover == Some(k) {
+            style.line_width = Some(style.line_width.unwrap_or(self.line_width) * 2.0)
+        }
         match data {
             GraphData::None => {}
+            GraphData::List(a) if self.graph_mode == GraphMode::ConformalGrid && !self.is_3d => {
+                self.draw_conformal(painter, k, a, &style)
+            }
             GraphData::List(a) => a.iter().for_each(|data| {
                 self.plot_type(painter, tex, buffer, k, data, cache, image_buffer)
             }),
+            GraphData::Bars(data) => {
+                if self.graph_mode == GraphMode::Normal && !self.is_3d {
+                    for (x, h, w) in data {
+                        self.draw_bar(
+                            painter,
+                            x - w * 0.5,
+                            x + w * 0.5,
+                            *h,
+                            &self.main_colors[k % self.main_colors.len()],
+                        )
+                    }
+                }
+            }
+            GraphData::Histogram(data, bins) => {
+                if self.graph_mode == GraphMode::Normal && !self.is_3d {
+                    let (start, width, bins) = self.binned(k, data, *bins);
+                    for (i, n) in bins.iter().copied().enumerate() {
+                        let x = start + i as f64 * width;
+                        self.draw_bar(
+                            painter,
+                            x,
+                            x + width,
+                            n as f64,
+                            &self.main_colors[k % self.main_colors.len()],
+                        )
+                    }
+                }
+            }
+            GraphData::CoordErr(data) => {
+                if self.graph_mode == GraphMode::Normal && !self.is_3d {
+                    for (x, y, ex, ey) in data {
+                        let (y, z) = y.to_options();
+                        if self.only_real && z.is_some_and(|z| z != 0.0) {
+                            continue;
+                        }
+                        if self.show.real()
+                            && let Some(y) = y
+                        {
+                            self.draw_whiskers(
+                                painter,
+                                *x,
+                                y,
+                                *ex,
+                                *ey,
+                                &self.main_colors[k % self.main_colors.len()],
+                            )
+                        }
+                        if self.show.imag()
+                            && !self.only_real
+                            && let Some(z) = z
+                        {
+                            self.draw_whiskers(
+                                painter,
+                                *x,
+                                z,
+                                *ex,
+                                *ey,
+                                &self.alt_colors[k % self.alt_colors.len()],
+                            )
+                        }
+                    }
+                }
+                let data = GraphData::Coord(data.iter().map(|(x, y, _, _)| (*x, *y)).collect());
+                self.plot_type(painter, tex, buffer, k, &data, cache, image_buffer)
+            }
+            GraphData::Band(data) => {
+                if self.graph_mode == GraphMode::Normal && !self.is_3d {
+                    self.draw_band(
+                        painter,
+                        data,
+                        &self.main_colors[k % self.main_colors.len()].with_alpha(self.fill_alpha),
+                    )
+                }
+            }
             GraphData::Width(data, start, end) => match self.graph_mode {
-                GraphMode::DomainColoring | GraphMode::Slice | GraphMode::SlicePolar => {}
+                GraphMode::DomainColoring
+                | GraphMode::Slice
+                | GraphMode::SlicePolar
+                | GraphMode::Implicit
+                | GraphMode::RiemannSphere
+                | GraphMode::ConformalGrid => {}
                 GraphMode::Normal => {
                     for (i, y) in data.iter().enumerate() {
                         let x = (i as f64 / (data.len() - 1) as f64 - 0.5) * (end - start)
@@ -2634,6 +4488,7 @@ impl Graph {
                                     z,
                                     &self.alt_colors[k % self.alt_colors.len()],
                                     b,
+                                    &style,
                                 )
                             }
                         } else {
@@ -2648,6 +4503,7 @@ impl Graph {
                                 y,
                                 &self.main_colors[k % self.main_colors.len()],
                                 a,
+                                &style,
                             )
                         } else {
                             None
@@ -2676,6 +4532,7 @@ impl Graph {
                                     s * z,
                                     &self.alt_colors[k % self.alt_colors.len()],
                                     b,
+                                    &style,
                                 )
                             }
                         } else {
@@ -2690,6 +4547,7 @@ impl Graph {
                                 s * y,
                                 &self.main_colors[k % self.main_colors.len()],
                                 a,
+                                &style,
                             )
                         } else {
                             None
@@ -2697,16 +4555,12 @@ impl Graph {
                     }
                 }
                 GraphMode::Flatten => {
-                    for y in data {
+                    for (i, y) in data.iter().enumerate() {
                         let (y, z) = y.to_options();
                         a = if let (Some(y), Some(z)) = (y, z) {
-                            self.draw_point(
-                                painter,
-                                y,
-                                z,
-                                &self.main_colors[k % self.main_colors.len()],
-                                a,
-                            )
+                            let t = (i as f64 / (data.len() - 1) as f64 - 0.5) * (end - start)
+                                + (start + end) * 0.5;
+                            self.draw_point(painter, y, z, &self.curve_color(k, t, y, z), a, &style)
                         } else {
                             None
                         };
@@ -2722,7 +4576,7 @@ impl Graph {
                                 x,
                                 y,
                                 z,
-                                &self.main_colors[k % self.main_colors.len()],
+                                &self.curve_color(k, z, x, y),
                                 c,
                                 None,
                                 buffer,
@@ -2735,7 +4589,12 @@ impl Graph {
                 }
             },
             GraphData::Coord(data) => match self.graph_mode {
-                GraphMode::DomainColoring | GraphMode::Slice | GraphMode::SlicePolar => {}
+                GraphMode::DomainColoring
+                | GraphMode::Slice
+                | GraphMode::SlicePolar
+                | GraphMode::Implicit
+                | GraphMode::RiemannSphere
+                | GraphMode::ConformalGrid => {}
                 GraphMode::Normal => {
                     for (x, y) in data {
                         let (y, z) = y.to_options();
@@ -2755,6 +4614,7 @@ impl Graph {
                                     z,
                                     &self.alt_colors[k % self.alt_colors.len()],
                                     b,
+                                    &style,
                                 )
                             }
                         } else {
@@ -2769,6 +4629,7 @@ impl Graph {
                                 y,
                                 &self.main_colors[k % self.main_colors.len()],
                                 a,
+                                &style,
                             )
                         } else {
                             None
@@ -2795,6 +4656,7 @@ impl Graph {
                                     s * z,
                                     &self.alt_colors[k % self.alt_colors.len()],
                                     b,
+                                    &style,
                                 )
                             }
                         } else {
@@ -2809,6 +4671,7 @@ impl Graph {
                                 s * y,
                                 &self.main_colors[k % self.main_colors.len()],
                                 a,
+                                &style,
                             )
                         } else {
                             None
@@ -2816,15 +4679,16 @@ impl Graph {
                     }
                 }
                 GraphMode::Flatten => {
-                    for (_, y) in data {
+                    for (t, y) in data {
                         let (y, z) = y.to_options();
                         a = if let (Some(y), Some(z)) = (y, z) {
                             self.draw_point(
                                 painter,
                                 y,
                                 z,
-                                &self.main_colors[k % self.main_colors.len()],
+                                &self.curve_color(k, *t, y, z),
                                 a,
+                                &style,
                             )
                         } else {
                             None
@@ -2839,7 +4703,7 @@ impl Graph {
                                 x,
                                 y,
                                 *i,
-                                &self.main_colors[k % self.main_colors.len()],
+                                &self.curve_color(k, *i, x, y),
                                 c,
                                 None,
                                 buffer,
@@ -2851,18 +4715,18 @@ impl Graph {
                     }
                 }
             },
-            GraphData::Width3D(data, start_x, start_y, end_x, end_y) => match self.graph_mode {
+            GraphData::Width3D(data, start_x, start_y, end_x, end_y, lx) => match self.graph_mode {
                 GraphMode::Normal => {
-                    let len = data.len().isqrt();
+                    let (len, leny) = grid_size(data.len(), *lx);
                     let mut last = Vec::with_capacity(len);
                     let mut cur = Vec::with_capacity(len);
                     let mut lasti = Vec::with_capacity(len);
                     let mut curi = Vec::with_capacity(len);
-                    for (i, z) in data.iter().enumerate() {
+                    for (i, z) in data.iter().take(len * leny).enumerate() {
                         let (i, j) = (i % len, i / len);
                         let x = (i as f64 / (len - 1) as f64 - 0.5) * (end_x - start_x)
                             + (start_x + end_x) * 0.5;
-                        let y = (j as f64 / (len - 1) as f64 - 0.5) * (end_y - start_y)
+                        let y = (j as f64 / (leny - 1) as f64 - 0.5) * (end_y - start_y)
                             + (start_y + end_y) * 0.5;
                         let (z, w) = z.to_options();
                         let p = if !self.show.imag() {
@@ -2922,16 +4786,16 @@ impl Graph {
                     }
                 }
                 GraphMode::Polar => {
-                    let len = data.len().isqrt();
+                    let (len, leny) = grid_size(data.len(), *lx);
                     let mut last = Vec::with_capacity(len);
                     let mut cur = Vec::with_capacity(len);
                     let mut lasti = Vec::with_capacity(len);
                     let mut curi = Vec::with_capacity(len);
-                    for (i, z) in data.iter().enumerate() {
+                    for (i, z) in data.iter().take(len * leny).enumerate() {
                         let (i, j) = (i % len, i / len);
                         let x = (i as f64 / (len - 1) as f64 - 0.5) * (end_x - start_x)
                             + (start_x + end_x) * 0.5;
-                        let y = (j as f64 / (len - 1) as f64 - 0.5) * (end_y - start_y)
+                        let y = (j as f64 / (leny - 1) as f64 - 0.5) * (end_y - start_y)
                             + (start_y + end_y) * 0.5;
                         let (ct, st) = x.sin_cos();
                         let (ca, sa) = y.sin_cos();
@@ -3023,6 +4887,7 @@ impl Graph {
                                     z,
                                     &self.alt_colors[k % self.alt_colors.len()],
                                     b,
+                                    &style,
                                 )
                             }
                         } else {
@@ -3037,6 +4902,7 @@ impl Graph {
                                 y,
                                 &self.main_colors[k % self.main_colors.len()],
                                 a,
+                                &style,
                             )
                         } else {
                             None
@@ -3075,6 +4941,7 @@ impl Graph {
                                     s * z,
                                     &self.alt_colors[k % self.alt_colors.len()],
                                     b,
+                                    &style,
                                 )
                             }
                         } else {
@@ -3089,6 +4956,7 @@ impl Graph {
                                 s * y,
                                 &self.main_colors[k % self.main_colors.len()],
                                 a,
+                                &style,
                             )
                         } else {
                             None
@@ -3096,22 +4964,24 @@ impl Graph {
                     }
                 }
                 GraphMode::Flatten => {
-                    let mut body = |y: &Complex| {
+                    let len = data.len();
+                    let mut body = |i: usize, y: &Complex| {
                         let (y, z) = y.to_options();
                         a = if let (Some(y), Some(z)) = (y, z) {
-                            self.draw_point(
-                                painter,
-                                y,
-                                z,
-                                &self.main_colors[k % self.main_colors.len()],
-                                a,
-                            )
+                            let t = if self.view_x {
+                                (i as f64 / (len - 1) as f64 - 0.5) * (end_x - start_x)
+                                    + (start_x + end_x) * 0.5
+                            } else {
+                                (i as f64 / (len - 1) as f64 - 0.5) * (end_y - start_y)
+                                    + (start_y + end_y) * 0.5
+                            };
+                            self.draw_point(painter, y, z, &self.curve_color(k, t, y, z), a, &style)
                         } else {
                             None
                         };
                     };
-                    for y in data.iter() {
-                        body(y)
+                    for (i, y) in data.iter().enumerate() {
+                        body(i, y)
                     }
                 }
                 GraphMode::Depth => {
@@ -3130,7 +5000,7 @@ impl Graph {
                                 x,
                                 y,
                                 z,
-                                &self.main_colors[k % self.main_colors.len()],
+                                &self.curve_color(k, z, x, y),
                                 c,
                                 None,
                                 buffer,
@@ -3145,8 +5015,10 @@ impl Graph {
                     }
                 }
                 GraphMode::DomainColoring => {
-                    let lenx = (self.screen.x * self.prec() * self.mult) as usize;
-                    let leny = (self.screen.y * self.prec() * self.mult) as usize;
+                    let (lenx, leny) = grid_size(data.len(), *lx);
+                    if lenx == 0 {
+                        return;
+                    }
                     if cache.is_none() {
                         #[cfg(feature = "egui")]
                         let m = 3;
@@ -3157,7 +5029,7 @@ impl Graph {
                         if c < n {
                             image_buffer.resize(n, 0);
                         }
-                        for (i, z) in data.iter().enumerate() {
+                        for (i, z) in data.iter().take(lenx * leny).enumerate() {
                             let [r, g, b] = self.get_color(z);
                             image_buffer[m * i] = r;
                             image_buffer[m * i + 1] = g;
@@ -3177,13 +5049,31 @@ impl Graph {
                         painter.image(texture, self.screen);
                     }
                 }
+                GraphMode::Implicit => self.draw_implicit(
+                    painter,
+                    data,
+                    grid_size(data.len(), *lx),
+                    (*start_x, *start_y, *end_x, *end_y),
+                    &self.main_colors[k % self.main_colors.len()],
+                ),
+                GraphMode::ConformalGrid => {}
+                GraphMode::RiemannSphere => self.draw_sphere(
+                    painter,
+                    buffer,
+                    data,
+                    grid_size(data.len(), *lx),
+                    (*start_x, *start_y, *end_x, *end_y),
+                ),
             },
             GraphData::Coord3D(data) => match self.graph_mode {
                 GraphMode::Slice
+                | GraphMode::Implicit
                 | GraphMode::DomainColoring
                 | GraphMode::Flatten
                 | GraphMode::Depth
-                | GraphMode::SlicePolar => {}
+                | GraphMode::SlicePolar
+                | GraphMode::RiemannSphere
+                | GraphMode::ConformalGrid => {}
                 GraphMode::Normal => {
                     let mut last = None;
                     let mut lasti = None;
@@ -3384,6 +5274,7 @@ impl Graph {
                                             z,
                                             &self.alt_colors[k % self.alt_colors.len()],
                                             b,
+                                            &style,
                                         )
                                     }
                                 } else {
@@ -3398,6 +5289,7 @@ impl Graph {
                                         y,
                                         &self.main_colors[k % self.main_colors.len()],
                                         a,
+                                        &style,
                                     )
                                 } else {
                                     None
@@ -3424,6 +5316,7 @@ impl Graph {
                                             x,
                                             &self.alt_colors[k % self.alt_colors.len()],
                                             b,
+                                            &style,
                                         )
                                     }
                                 } else {
@@ -3438,6 +5331,7 @@ impl Graph {
                                         x,
                                         &self.main_colors[k % self.main_colors.len()],
                                         a,
+                                        &style,
                                     )
                                 } else {
                                     None
@@ -3476,10 +5370,16 @@ impl Graph {
                         }
                     }
                 }
-                GraphMode::DomainColoring | GraphMode::Depth | GraphMode::Flatten => {}
+                GraphMode::DomainColoring
+                | GraphMode::Depth
+                | GraphMode::Flatten
+                | GraphMode::Implicit
+                | GraphMode::RiemannSphere
+                | GraphMode::ConformalGrid => {}
             },
             GraphData::Point(p) => match self.graph_mode {
                 GraphMode::DomainColoring //TODO fix dc
+                | GraphMode::Implicit
                 | GraphMode::Flatten
                 | GraphMode::Normal
                 | GraphMode::Slice => {
@@ -3500,46 +5400,541 @@ impl Graph {
                          self.point_size)
                     }
                 }
-                GraphMode::Depth => {}
+                GraphMode::Depth | GraphMode::RiemannSphere | GraphMode::ConformalGrid => {}
             },
         }
     }
     fn get_color(&self, z: &Complex) -> [u8; 3] {
+        if let Some(f) = &self.domain_color {
+            let c = f(z);
+            return rgb2val(c.r as f64 / 255.0, c.g as f64 / 255.0, c.b as f64 / 255.0);
+        }
+        let [r, g, b] = self.domain_rgb(z);
+        rgb2val(r, g, b)
+    }
+    fn domain_color(&self, z: &Complex) -> Color {
+        if let Some(f) = &self.domain_color {
+            return f(z);
+        }
+        let [r, g, b] = self.domain_rgb(z);
+        Color::new((255.0 * r) as u8, (255.0 * g) as u8, (255.0 * b) as u8)
+    }
+    ///rgb in 0..1 of a value under domain_scheme
+    fn domain_rgb(&self, z: &Complex) -> [f64; 3] {
         let (x, y) = z.to_options();
         let (x, y) = (x.unwrap_or(0.0), y.unwrap_or(0.0));
         let hue = 6.0 * (1.0 - y.atan2(x) / TAU);
         let abs = x.hypot(y);
-        let (sat, val) = if self.domain_alternate {
-            let sat = (if self.log_scale { abs.log10() } else { abs } * PI)
-                .sin()
-                .abs()
-                .powf(0.125);
-            let n1 = x.abs() / (x.abs() + 1.0);
-            let n2 = y.abs() / (y.abs() + 1.0);
-            let n3 = (n1 * n2).powf(0.0625);
-            let n4 = abs.atan() * 2.0 / PI;
-            let lig = 0.8 * (n3 * (n4 - 0.5) + 0.5);
-            let val = if lig < 0.5 {
-                lig * (1.0 + sat)
+        let (sat, val) = match self.domain_scheme {
+            DomainColorScheme::Default if self.domain_alternate => {
+                let sat = (if self.log_scale { abs.log10() } else { abs } * PI)
+                    .sin()
+                    .abs()
+                    .powf(0.125);
+                let n1 = x.abs() / (x.abs() + 1.0);
+                let n2 = y.abs() / (y.abs() + 1.0);
+                let n3 = (n1 * n2).powf(0.0625);
+                let n4 = abs.atan() * 2.0 / PI;
+                let lig = 0.8 * (n3 * (n4 - 0.5) + 0.5);
+                let val = if lig < 0.5 {
+                    lig * (1.0 + sat)
+                } else {
+                    lig * (1.0 - sat) + sat
+                };
+                let sat = if val == 0.0 {
+                    0.0
+                } else {
+                    2.0 * (1.0 - lig / val)
+                };
+                (sat, val)
+            }
+            DomainColorScheme::Default => {
+                let t1 = (if self.log_scale { x.abs().log10() } else { x } * PI).sin();
+                let t2 = (if self.log_scale { y.abs().log10() } else { y } * PI).sin();
+                let sat = (1.0 + if self.log_scale { abs.log10() } else { abs }.fract()) * 0.5;
+                let val = (t1 * t2).abs().powf(0.125);
+                (sat, val)
+            }
+            DomainColorScheme::Hsv => {
+                let lig = abs.atan() * 2.0 / PI;
+                if lig < 0.5 {
+                    (1.0, 2.0 * lig)
+                } else {
+                    (2.0 * (1.0 - lig), 1.0)
+                }
+            }
+            DomainColorScheme::Enhanced => {
+                let m = abs.log2().rem_euclid(1.0);
+                let a = (2.0 * hue).rem_euclid(1.0);
+                (1.0, 0.6 + 0.4 * (m * a).sqrt())
+            }
+            DomainColorScheme::Checkerboard => {
+                let m = abs.log2().floor();
+                let a = (2.0 * hue).floor();
+                let even = (m + a).rem_euclid(2.0) < 1.0;
+                (1.0, if even { 1.0 } else { 0.7 })
+            }
+            DomainColorScheme::Oklch => {
+                let lig = 0.1 + 0.85 * abs.atan() * 2.0 / PI;
+                let chroma = 0.13 * (4.0 * lig * (1.0 - lig)).min(1.0);
+                let arg = y.atan2(x);
+                let mut c = [
+                    lig as f32,
+                    (chroma * arg.cos()) as f32,
+                    (chroma * arg.sin()) as f32,
+                ];
+                oklch_to_rgb(&mut c);
+                return c.map(|c| (c as f64).clamp(0.0, 1.0));
+            }
+        };
+        hsv2rgb(hue, sat, val)
+    }
+    ///zeros and poles of every Width3D data set, found from the winding of the argument
+    ///around each grid cell and refined with a linear fit inside the cell
+    pub fn zeros_poles(&self) -> Vec<ZeroPole> {
+        let mut found = Vec::new();
+        for (k, data) in self.data.iter().enumerate() {
+            if let GraphData::Width3D(data, start_x, start_y, end_x, end_y, lx) = data {
+                found.extend(find_zeros_poles(
+                    k,
+                    data,
+                    grid_size(data.len(), *lx),
+                    (*start_x, *start_y, *end_x, *end_y),
+                ))
+            }
+        }
+        found
+    }
+    ///low and high end of Graph.gradient over every data set,
+    ///none unless the gradient applies to the current mode
+    fn get_gradient_range(&self) -> Option<(f64, f64)> {
+        if !matches!(self.graph_mode, GraphMode::Flatten | GraphMode::Depth) {
+            return None;
+        }
+        let (mut lo, mut hi) = (f64::INFINITY, f64::NEG_INFINITY);
+        let mut push = |v: f64| {
+            if v.is_finite() {
+                lo = lo.min(v);
+                hi = hi.max(v)
+            }
+        };
+        match self.gradient {
+            Gradient::None => return None,
+            Gradient::Argument => return Some((-PI, PI)),
+            Gradient::Parameter => {
+                for data in &self.data {
+                    match data {
+                        GraphData::Width(_, start, end) => {
+                            push(*start);
+                            push(*end)
+                        }
+                        GraphData::Coord(data) => data.iter().for_each(|(t, _)| push(*t)),
+                        GraphData::Width3D(_, start_x, start_y, end_x, end_y, _) => {
+                            if self.view_x {
+                                push(*start_x);
+                                push(*end_x)
+                            } else {
+                                push(*start_y);
+                                push(*end_y)
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Gradient::Modulus => {
+                let mut body = |z: &Complex| {
+                    if let (Some(a), Some(b)) = z.to_options() {
+                        push(a.hypot(b))
+                    }
+                };
+                for data in &self.data {
+                    match data {
+                        GraphData::Width(data, _, _) | GraphData::Width3D(data, _, _, _, _, _) => {
+                            data.iter().for_each(&mut body)
+                        }
+                        GraphData::Coord(data) => data.iter().for_each(|(_, z)| body(z)),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        (lo <= hi).then_some((lo, hi))
+    }
+    ///color of the curve k at parameter t with value a+bi
+    fn curve_color(&self, k: usize, t: f64, a: f64, b: f64) -> Color {
+        let Some((lo, hi)) = self.gradient_range else {
+            return self.main_colors[k % self.main_colors.len()];
+        };
+        let v = match self.gradient {
+            Gradient::None => return self.main_colors[k % self.main_colors.len()],
+            Gradient::Parameter => t,
+            Gradient::Modulus => a.hypot(b),
+            Gradient::Argument => b.atan2(a),
+        };
+        let t = (v - lo) / (hi - lo);
+        self.gradient_color(if t.is_finite() { t } else { 0.5 })
+    }
+    ///color at t in 0..1 along gradient_colors
+    fn gradient_color(&self, t: f64) -> Color {
+        let n = self.gradient_colors.len();
+        if n < 2 {
+            return self
+                .gradient_colors
+                .first()
+                .copied()
+                .unwrap_or(self.axis_color);
+        }
+        let f = t.clamp(0.0, 1.0) * (n - 1) as f64;
+        let i = (f.floor() as usize).min(n - 2);
+        let f = f - i as f64;
+        let (a, b) = (self.gradient_colors[i], self.gradient_colors[i + 1]);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * f).round() as u8;
+        Color::rgba(mix(a.r, b.r), mix(a.g, b.g), mix(a.b, b.b), mix(a.a, b.a))
+    }
+    ///color bar for Graph.gradient along the right edge
+    fn write_gradient_bar(&self, painter: &mut Painter) {
+        let Some((lo, hi)) = self.gradient_range else {
+            return;
+        };
+        let (top, bottom) = self.title_margins();
+        let (sx, sy) = (self.screen.x as f32, self.screen.y as f32);
+        let (y0, y1) = (
+            top + (sy - top - bottom) * 0.25,
+            sy - bottom - (sy - top - bottom) * 0.25,
+        );
+        let (x0, x1) = (sx - 20.0, sx - 8.0);
+        let steps = (y1 - y0).max(1.0) as usize;
+        for n in 0..steps {
+            let y = y1 - n as f32;
+            painter.fill_rect(
+                Pos::new(x0, y - 1.0),
+                Pos::new(x1, y),
+                &self.gradient_color(n as f64 / (steps - 1).max(1) as f64),
+            )
+        }
+        for (a, b) in [
+            (Pos::new(x0, y0), Pos::new(x1, y0)),
+            (Pos::new(x1, y0), Pos::new(x1, y1)),
+            (Pos::new(x1, y1), Pos::new(x0, y1)),
+            (Pos::new(x0, y1), Pos::new(x0, y0)),
+        ] {
+            painter.line_segment([a, b], 1.0, &self.axis_color)
+        }
+        for (v, y) in [(hi, y0), ((lo + hi) * 0.5, (y0 + y1) * 0.5), (lo, y1)] {
+            let v = if self.gradient == Gradient::Argument {
+                self.angle_type.to_val(v)
             } else {
-                lig * (1.0 - sat) + sat
+                v
             };
-            let sat = if val == 0.0 {
-                0.0
+            let mut s = v.to_string();
+            if s.len() > 8 {
+                s = format!("{v:E}")
+            }
+            self.text(
+                Pos::new(x0 - 4.0, y),
+                Align::RightCenter,
+                &s,
+                &self.text_color,
+                painter,
+            );
+        }
+    }
+    ///branch cuts of every Width3D data set as short segments in graph coordinates, each lying
+    ///between two neighbouring samples whose argument jumps by more than branch_cut_threshold
+    ///while the modulus around them stays continuous
+    pub fn branch_cuts(&self) -> Vec<[Vec2; 2]> {
+        let mut found = Vec::new();
+        for data in &self.data {
+            if let GraphData::Width3D(data, start_x, start_y, end_x, end_y, lx) = data {
+                found.extend(find_branch_cuts(
+                    data,
+                    grid_size(data.len(), *lx),
+                    (*start_x, *start_y, *end_x, *end_y),
+                    self.branch_cut_threshold,
+                ))
+            }
+        }
+        found
+    }
+    ///the data vertex drawn nearest to p in 3d, within a few pixels and in front of the
+    ///others there, with p in the same coordinates as the mouse position
+    pub fn pick(&self, p: Vec2) -> Option<Picked> {
+        if !self.is_3d {
+            return None;
+        }
+        let mut found = Vec::new();
+        for (k, data) in self.data.iter().enumerate() {
+            self.vertices(k, data, &mut found)
+        }
+        let r = 12.0;
+        let hits = found
+            .into_iter()
+            .filter_map(|v| {
+                let (pos, depth) = self.project_3d(v.x, v.y, v.z)?;
+                let d = (pos.x as f64 - p.x).powi(2) + (pos.y as f64 - p.y).powi(2);
+                (d <= r * r).then_some((d, depth, v))
+            })
+            .collect::<Vec<_>>();
+        //the far side of a surface projects under the cursor as well
+        let front = hits.iter().map(|h| h.1).fold(f64::NEG_INFINITY, f64::max);
+        hits.into_iter()
+            .filter(|h| h.1 >= front - 0.02)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|h| h.2)
+    }
+    ///pins the vertex under the cursor, or unpins it if it already is
+    pub fn toggle_pin(&mut self) {
+        let Some(h) = self.hover else {
+            return;
+        };
+        if let Some(n) = self
+            .pins
+            .iter()
+            .position(|p| p.data == h.data && p.x == h.x && p.y == h.y && p.z == h.z)
+        {
+            self.pins.remove(n);
+        } else {
+            self.pins.push(h)
+        }
+    }
+    ///every vertex a data set draws in 3d, at where it is drawn before offset3d
+    fn vertices(&self, k: usize, data: &GraphData, out: &mut Vec<Picked>) {
+        if let GraphData::List(a) = data {
+            for data in a {
+                self.vertices(k, data, out)
+            }
+            return;
+        }
+        let mut push = |x: f64, y: f64, z: f64, value: &Complex| {
+            out.push(Picked {
+                data: k,
+                x,
+                y,
+                z,
+                value: *value,
+            })
+        };
+        let place = |x: f64, y: f64, z: f64| {
+            if self.graph_mode == GraphMode::Polar {
+                let (ct, st) = x.sin_cos();
+                let (ca, sa) = y.sin_cos();
+                (z * st * ca, z * st * sa, z * ct)
             } else {
-                2.0 * (1.0 - lig / val)
+                (x, y, z)
+            }
+        };
+        let param = |i: usize, len: usize, start: f64, end: f64| {
+            (i as f64 / (len - 1) as f64 - 0.5) * (end - start) + (start + end) * 0.5
+        };
+        match (data, self.graph_mode) {
+            (
+                GraphData::Width3D(data, start_x, start_y, end_x, end_y, lx),
+                GraphMode::Normal | GraphMode::Polar,
+            ) => {
+                let (len, leny) = grid_size(data.len(), *lx);
+                for (i, v) in data.iter().take(len * leny).enumerate() {
+                    let x = param(i % len, len, *start_x, *end_x);
+                    let y = param(i / len, leny, *start_y, *end_y);
+                    for z in self.heights(v).into_iter().flatten() {
+                        let (x, y, z) = place(x, y, z);
+                        push(x, y, z, v)
+                    }
+                }
+            }
+            (GraphData::Coord3D(data), GraphMode::Normal | GraphMode::Polar) => {
+                for (x, y, v) in data {
+                    for z in self.heights(v).into_iter().flatten() {
+                        let (x, y, z) = place(*x, *y, z);
+                        push(x, y, z, v)
+                    }
+                }
+            }
+            (GraphData::Width(data, start, end), GraphMode::Depth) => {
+                for (i, v) in data.iter().enumerate() {
+                    if let (Some(x), Some(y)) = v.to_options() {
+                        push(x, y, param(i, data.len(), *start, *end), v)
+                    }
+                }
+            }
+            (GraphData::Coord(data), GraphMode::Depth) => {
+                for (t, v) in data {
+                    if let (Some(x), Some(y)) = v.to_options() {
+                        push(x, y, *t, v)
+                    }
+                }
+            }
+            (GraphData::Width3D(data, start_x, start_y, end_x, end_y, _), GraphMode::Depth) => {
+                let (start, end) = if self.view_x {
+                    (start_x, end_x)
+                } else {
+                    (start_y, end_y)
+                };
+                for (i, v) in data.iter().enumerate() {
+                    if let (Some(x), Some(y)) = v.to_options() {
+                        push(x, y, param(i, data.len(), *start, *end), v)
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    ///heights a sample is drawn at on the real and the imaginary surface in 3d
+    fn heights(&self, v: &Complex) -> [Option<f64>; 2] {
+        let (z, w) = v.to_options();
+        if self.only_real && w.is_some_and(|w| w != 0.0) {
+            return [None, None];
+        }
+        [
+            z.filter(|_| self.show.real()),
+            w.filter(|_| self.show.imag() && !self.only_real),
+        ]
+    }
+    fn write_picks(&self, painter: &mut Painter) {
+        if !self.is_3d {
+            return;
+        }
+        let r = 5.0;
+        for (h, pinned) in self
+            .pins
+            .iter()
+            .map(|p| (p, true))
+            .chain(self.hover.iter().map(|p| (p, false)))
+        {
+            let Some((p, _)) = self.project_3d(h.x, h.y, h.z) else {
+                continue;
             };
-            (sat, val)
-        } else {
-            let t1 = (if self.log_scale { x.abs().log10() } else { x } * PI).sin();
-            let t2 = (if self.log_scale { y.abs().log10() } else { y } * PI).sin();
-            let sat = (1.0 + if self.log_scale { abs.log10() } else { abs }.fract()) * 0.5;
-            let val = (t1 * t2).abs().powf(0.125);
-            (sat, val)
+            //outlined so the marker shows over any surface color
+            for (width, color) in [(4.0, &self.background_color), (2.0, &self.axis_color)] {
+                painter.circle(p, r, color, width);
+            }
+            if pinned {
+                self.text(
+                    Pos::new(p.x + r + 2.0, p.y - r),
+                    Align::LeftBottom,
+                    &format!("{:E}\n{:E}\n{:E}", h.x, h.y, h.z),
+                    &self.text_color,
+                    painter,
+                );
+            }
+        }
+    }
+    fn write_branch_cuts(&self, painter: &mut Painter) {
+        if !self.show_branch_cuts {
+            return;
+        }
+        let Some(found) = &self.branch_cut_cache else {
+            return;
         };
-        hsv2rgb(hue, sat, val)
+        let segments = match self.graph_mode {
+            //on the sphere the cuts are in longitude and latitude, only the near side shows
+            GraphMode::RiemannSphere => found
+                .iter()
+                .filter_map(|[a, b]| {
+                    let (a, b) = (self.sphere_point(a.x, a.y), self.sphere_point(b.x, b.y));
+                    (a.1 + b.1 > 0.0).then_some([a.0.0, b.0.0])
+                })
+                .collect::<Vec<[Pos; 2]>>(),
+            GraphMode::DomainColoring if !self.is_3d => found
+                .iter()
+                .map(|[a, b]| [self.to_screen(a.x, a.y), self.to_screen(b.x, b.y)])
+                .filter(|[a, b]| self.in_screen(*a) || self.in_screen(*b))
+                .collect::<Vec<[Pos; 2]>>(),
+            _ => return,
+        };
+        //outline first so neighbouring segments do not cover each other's line
+        for (width, color) in [(4.0, &self.background_color), (2.0, &self.axis_color)] {
+            for s in &segments {
+                painter.line_segment(*s, width, color)
+            }
+        }
+    }
+    fn write_zeros_poles(&self, painter: &mut Painter) {
+        if !self.show_zeros_poles || self.graph_mode != GraphMode::DomainColoring || self.is_3d {
+            return;
+        }
+        let Some(found) = &self.zero_pole_cache else {
+            return;
+        };
+        let r = 5.0;
+        for z in found {
+            let p = self.to_screen(z.x, z.y);
+            if !self.in_screen(p) {
+                continue;
+            }
+            //outlined so the glyph shows on both dark and light regions
+            for (width, color) in [(4.0, &self.background_color), (2.0, &self.axis_color)] {
+                if z.order > 0 {
+                    painter.circle(p, r, color, width);
+                } else {
+                    painter.line_segment(
+                        [Pos::new(p.x - r, p.y - r), Pos::new(p.x + r, p.y + r)],
+                        width,
+                        color,
+                    );
+                    painter.line_segment(
+                        [Pos::new(p.x - r, p.y + r), Pos::new(p.x + r, p.y - r)],
+                        width,
+                        color,
+                    );
+                }
+            }
+            if z.order.abs() > 1 {
+                self.text(
+                    Pos::new(p.x + r + 2.0, p.y - r),
+                    Align::LeftBottom,
+                    &z.order.abs().to_string(),
+                    &self.text_color,
+                    painter,
+                );
+            }
+        }
+    }
+    ///hue wheel key for domain coloring, the rim is at modulus 2 and the ring at modulus 1
+    fn write_domain_wheel(&self, painter: &mut Painter) {
+        //the sphere is domain coloring too, so it gets the wheel
+        let drawn = self.graph_mode == GraphMode::RiemannSphere
+            || (self.graph_mode == GraphMode::DomainColoring && !self.is_3d);
+        if !self.domain_wheel || !drawn {
+            return;
+        }
+        let r = 48.0;
+        let (_, bottom) = self.title_margins();
+        let c = Pos::new(
+            self.screen.x as f32 - r - self.font_width * 2.0 - 8.0,
+            self.screen.y as f32 - r - bottom - self.font_size - 8.0,
+        );
+        let step = 2;
+        for i in (-r as i32..=r as i32).step_by(step) {
+            for j in (-r as i32..=r as i32).step_by(step) {
+                let (dx, dy) = (i as f32, j as f32);
+                if dx * dx + dy * dy > r * r {
+                    continue;
+                }
+                let z = Complex::Complex((2.0 * dx / r) as f64, (-2.0 * dy / r) as f64);
+                painter.rect_filled(
+                    Pos::new(c.x + dx, c.y + dy),
+                    &self.domain_color(&z),
+                    step as f32,
+                );
+            }
+        }
+        painter.circle(c, r, &self.axis_color, 1.0);
+        painter.circle(c, r * 0.5, &self.axis_color, 1.0);
+        for (s, p, align) in [
+            ("1", Pos::new(c.x + r + 2.0, c.y), Align::LeftCenter),
+            ("i", Pos::new(c.x, c.y - r - 2.0), Align::CenterBottom),
+            ("-1", Pos::new(c.x - r - 2.0, c.y), Align::RightCenter),
+            ("-i", Pos::new(c.x, c.y + r + 2.0), Align::CenterTop),
+        ] {
+            self.text(p, align, s, &self.text_color, painter);
+        }
     }
     fn shift_hue(&self, diff: Option<f32>, z: f64, color: &Color) -> Color {
+        if self.gradient_range.is_some() {
+            //the gradient is what the color means, do not shade it by depth
+            return *color;
+        }
         match diff {
             Some(diff) => match self.color_depth {
                 DepthColor::Vertical => {
@@ -3616,9 +6011,418 @@ impl Graph {
         }
     }
 }
-fn hsv2rgb(hue: f64, sat: f64, val: f64) -> [u8; 3] {
+///reduces samples to the first, min, max and last value in each pixel column
+fn decimate<I>(samples: I, scale: f64, only_real: bool) -> GraphData
+where
+    I: Iterator<Item = (f64, Complex)> + Clone,
+{
+    let real = envelope(
+        samples.clone().map(|(x, y)| {
+            let (y, z) = y.to_options();
+            if only_real && z.is_some_and(|z| z != 0.0) {
+                (x, f64::NAN)
+            } else {
+                (x, y.unwrap_or(f64::NAN))
+            }
+        }),
+        scale,
+        Complex::Real,
+    );
+    let imag = if only_real {
+        Vec::new()
+    } else {
+        envelope(
+            samples.map(|(x, y)| (x, y.to_options().1.unwrap_or(f64::NAN))),
+            scale,
+            Complex::Imag,
+        )
+    };
+    if imag
+        .iter()
+        .any(|(_, z)| z.to_options().1.is_some_and(|z| z.is_finite()))
+    {
+        GraphData::List(vec![GraphData::Coord(real), GraphData::Coord(imag)])
+    } else {
+        GraphData::Coord(real)
+    }
+}
+fn envelope<I>(samples: I, scale: f64, to: fn(f64) -> Complex) -> Vec<(f64, Complex)>
+where
+    I: Iterator<Item = (f64, f64)>,
+{
+    struct Column {
+        n: f64,
+        first: (f64, f64),
+        min: (f64, f64, usize),
+        max: (f64, f64, usize),
+        last: (f64, f64),
+    }
+    impl Column {
+        fn push(self, out: &mut Vec<(f64, Complex)>, to: fn(f64) -> Complex) {
+            out.push((self.first.0, to(self.first.1)));
+            let (a, b) = if self.min.2 < self.max.2 {
+                (self.min, self.max)
+            } else {
+                (self.max, self.min)
+            };
+            for (x, y, _) in [a, b] {
+                if (x, y) != self.first && (x, y) != self.last {
+                    out.push((x, to(y)))
+                }
+            }
+            if self.last != self.first {
+                out.push((self.last.0, to(self.last.1)))
+            }
+        }
+    }
+    let mut out = Vec::new();
+    let mut col: Option<Column> = None;
+    for (i, (x, y)) in samples.enumerate() {
+        if !x.is_finite() || !y.is_finite() {
+            if let Some(col) = col.take() {
+                col.push(&mut out, to)
+            }
+            out.push((x, to(f64::NAN)));
+            continue;
+        }
+        let n = (x * scale).floor();
+        match &mut col {
+            Some(col) if col.n == n => {
+                if y < col.min.1 {
+                    col.min = (x, y, i)
+                }
+                if y > col.max.1 {
+                    col.max = (x, y, i)
+                }
+                col.last = (x, y)
+            }
+            _ => {
+                if let Some(col) = col.take() {
+                    col.push(&mut out, to)
+                }
+                col = Some(Column {
+                    n,
+                    first: (x, y),
+                    min: (x, y, i),
+                    max: (x, y, i),
+                    last: (x, y),
+                })
+            }
+        }
+    }
+    if let Some(col) = col {
+        col.push(&mut out, to)
+    }
+    out
+}
+///the given axis title, or the axis letter if there is none
+fn axis_name<'a>(letter: &'a str, title: &'a str) -> &'a str {
+    if title.is_empty() { letter } else { title }
+}
+///linearly interpolates the data at x, expects data sorted by x
+fn interpolate(data: &[(f64, f64)], x: f64) -> Option<f64> {
+    let i = data.partition_point(|(a, _)| *a < x);
+    let (x1, y1) = *data.get(i)?;
+    let y = if x1 == x {
+        y1
+    } else {
+        let (x0, y0) = *data.get(i.checked_sub(1)?)?;
+        y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+    };
+    y.is_finite().then_some(y)
+}
+fn find_zeros_poles(
+    k: usize,
+    data: &[Complex],
+    (lenx, leny): (usize, usize),
+    (start_x, start_y, end_x, end_y): (f64, f64, f64, f64),
+) -> Vec<ZeroPole> {
+    if lenx < 3 || leny < 3 || data.len() < lenx * leny {
+        return Vec::new();
+    }
+    let (dx, dy) = (
+        (end_x - start_x) / (lenx - 1) as f64,
+        (end_y - start_y) / (leny - 1) as f64,
+    );
+    let val = |(i, j): (usize, usize)| {
+        let (a, b) = data[i + lenx * j].to_options();
+        (a.unwrap_or(0.0), b.unwrap_or(0.0))
+    };
+    //the loop runs counter clockwise only when both axes increase with the index
+    let winding = |path: &[(usize, usize)]| {
+        let arg = |p| {
+            let (a, b) = val(p);
+            b.atan2(a)
+        };
+        let turn = (0..path.len())
+            .map(|n| (arg(path[(n + 1) % path.len()]) - arg(path[n]) + PI).rem_euclid(TAU) - PI)
+            .sum::<f64>();
+        if !turn.is_finite() {
+            return 0;
+        }
+        let order = (turn / TAU).round() as i32;
+        if dx * dy < 0.0 { -order } else { order }
+    };
+    let cell = |i: usize, j: usize| [(i, j), (i + 1, j), (i + 1, j + 1), (i, j + 1)];
+    //loops around 2x2 cells, so the argument turns less than half a turn per step
+    //for orders up to 3, which a single cell can not resolve past order 1
+    let block = |i: usize, j: usize| {
+        [
+            (i, j),
+            (i + 1, j),
+            (i + 2, j),
+            (i + 2, j + 1),
+            (i + 2, j + 2),
+            (i + 1, j + 2),
+            (i, j + 2),
+            (i, j + 1),
+        ]
+    };
+    let row = |j: usize| {
+        (0..lenx - 2)
+            .filter_map(|i| {
+                let w = winding(&block(i, j));
+                (w != 0).then_some((i, j, w))
+            })
+            .collect::<Vec<(usize, usize, i32)>>()
+    };
+    #[cfg(feature = "rayon")]
+    let blocks = (0..leny - 2)
+        .into_par_iter()
+        .flat_map_iter(row)
+        .collect::<Vec<(usize, usize, i32)>>();
+    #[cfg(not(feature = "rayon"))]
+    let blocks = (0..leny - 2)
+        .flat_map(row)
+        .collect::<Vec<(usize, usize, i32)>>();
+    //a point lies inside up to four overlapping blocks
+    let mut clusters: Vec<Vec<(usize, usize, i32)>> = Vec::new();
+    for b in blocks {
+        let c = clusters.iter_mut().rev().find(|c| {
+            c[0].2.signum() == b.2.signum()
+                && c.iter()
+                    .any(|m| m.0.abs_diff(b.0) <= 1 && m.1.abs_diff(b.1) <= 1)
+        });
+        if let Some(c) = c {
+            c.push(b)
+        } else {
+            clusters.push(vec![b])
+        }
+    }
+    //a simple zero of f, or of 1/f for a pole, is where its linear fit over the cell vanishes
+    let linear = |(i, j): (usize, usize), order: i32| {
+        let w = |p| {
+            let (a, b) = val(p);
+            if order > 0 {
+                (a, b)
+            } else {
+                let r = a * a + b * b;
+                (a / r, -b / r)
+            }
+        };
+        let (f00, f10, f01) = (w((i, j)), w((i + 1, j)), w((i, j + 1)));
+        let (fx, fy) = (
+            (f10.0 - f00.0, f10.1 - f00.1),
+            (f01.0 - f00.0, f01.1 - f00.1),
+        );
+        let det = fx.0 * fy.1 - fy.0 * fx.1;
+        let u = (fy.0 * f00.1 - f00.0 * fy.1) / det;
+        let t = (f00.0 * fx.1 - fx.0 * f00.1) / det;
+        (u.is_finite() && t.is_finite())
+            .then(|| (i as f64 + u.clamp(0.0, 1.0), j as f64 + t.clamp(0.0, 1.0)))
+    };
+    clusters
+        .into_iter()
+        .map(|c| {
+            let order = c.iter().map(|b| b.2).max_by_key(|w| w.abs()).unwrap_or(0);
+            let (i0, j0) = (
+                c.iter().map(|b| b.0).min().unwrap_or(0),
+                c.iter().map(|b| b.1).min().unwrap_or(0),
+            );
+            let (i1, j1) = (
+                c.iter().map(|b| b.0).max().unwrap_or(0) + 2,
+                c.iter().map(|b| b.1).max().unwrap_or(0) + 2,
+            );
+            let fit = (order.abs() == 1)
+                .then(|| {
+                    (j0..j1)
+                        .flat_map(|j| (i0..i1).map(move |i| (i, j)))
+                        .find(|(i, j)| winding(&cell(*i, *j)) == order)
+                        .and_then(|p| linear(p, order))
+                })
+                .flatten();
+            //otherwise the grid point where |f| is smallest, or largest for a pole
+            let (u, t) = fit.unwrap_or_else(|| {
+                (j0..=j1)
+                    .flat_map(|j| (i0..=i1).map(move |i| (i, j)))
+                    .map(|p| {
+                        let (a, b) = val(p);
+                        (p, a.hypot(b).ln() * order.signum() as f64)
+                    })
+                    .filter(|(_, m)| !m.is_nan())
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map_or((i0 as f64 + 1.0, j0 as f64 + 1.0), |((i, j), _)| {
+                        (i as f64, j as f64)
+                    })
+            });
+            ZeroPole {
+                data: k,
+                x: start_x + u * dx,
+                y: start_y + t * dy,
+                order,
+            }
+        })
+        .fold(Vec::new(), |mut found: Vec<ZeroPole>, z| {
+            //blocks skimming past a point can split its cluster, keep the strongest reading
+            let near = found.iter_mut().find(|m| {
+                m.order.signum() == z.order.signum()
+                    && (m.x - z.x).abs() <= 2.0 * dx.abs()
+                    && (m.y - z.y).abs() <= 2.0 * dy.abs()
+            });
+            match near {
+                Some(m) if m.order.abs() < z.order.abs() => *m = z,
+                Some(_) => {}
+                None => found.push(z),
+            }
+            found
+        })
+}
+///dimensions of a Width3D grid, square if len_x is none,
+///(0, 0) when there are not two rows and two columns to span the bounds with
+fn grid_size(len: usize, len_x: Option<usize>) -> (usize, usize) {
+    let len_x = len_x.unwrap_or(len.isqrt());
+    if len_x < 2 || len / len_x < 2 {
+        (0, 0)
+    } else {
+        (len_x, len / len_x)
+    }
+}
+///marching squares, the segments where the real part of a Width3D grid crosses zero
+fn contour(
+    data: &[Complex],
+    (lenx, leny): (usize, usize),
+    (start_x, start_y, end_x, end_y): (f64, f64, f64, f64),
+) -> Vec<(Vec2, Vec2)> {
+    if lenx < 2 || leny < 2 || data.len() < lenx * leny {
+        return Vec::new();
+    }
+    let (dx, dy) = (
+        (end_x - start_x) / (lenx - 1) as f64,
+        (end_y - start_y) / (leny - 1) as f64,
+    );
+    let row = |j: usize| {
+        let val = |i: usize, j: usize| data[i + lenx * j].to_options().0.unwrap_or(f64::NAN);
+        let corner = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+        let mut segments = Vec::new();
+        for i in 0..lenx - 1 {
+            let v = [val(i, j), val(i + 1, j), val(i + 1, j + 1), val(i, j + 1)];
+            if v.iter().any(|v| !v.is_finite()) {
+                continue;
+            }
+            let edge = |e: usize| {
+                let (a, b) = (e, (e + 1) % 4);
+                let t = v[a] / (v[a] - v[b]);
+                let ((x0, y0), (x1, y1)) = (corner[a], corner[b]);
+                Vec2::new(
+                    start_x + (i as f64 + x0 + (x1 - x0) * t) * dx,
+                    start_y + (j as f64 + y0 + (y1 - y0) * t) * dy,
+                )
+            };
+            let crossed = (0..4)
+                .filter(|e| (v[*e] > 0.0) != (v[(e + 1) % 4] > 0.0))
+                .collect::<Vec<usize>>();
+            match crossed.len() {
+                2 => segments.push((edge(crossed[0]), edge(crossed[1]))),
+                4 => {
+                    let center = v.iter().sum::<f64>() > 0.0;
+                    if center == (v[0] > 0.0) {
+                        segments.push((edge(0), edge(1)));
+                        segments.push((edge(2), edge(3)));
+                    } else {
+                        segments.push((edge(3), edge(0)));
+                        segments.push((edge(1), edge(2)));
+                    }
+                }
+                _ => {}
+            }
+        }
+        segments
+    };
+    #[cfg(feature = "rayon")]
+    let segments = (0..leny - 1)
+        .into_par_iter()
+        .flat_map_iter(row)
+        .collect::<Vec<(Vec2, Vec2)>>();
+    #[cfg(not(feature = "rayon"))]
+    let segments = (0..leny - 1).flat_map(row).collect::<Vec<(Vec2, Vec2)>>();
+    segments
+}
+fn find_branch_cuts(
+    data: &[Complex],
+    (lenx, leny): (usize, usize),
+    (start_x, start_y, end_x, end_y): (f64, f64, f64, f64),
+    threshold: f64,
+) -> Vec<[Vec2; 2]> {
+    if lenx < 4 || leny < 4 || data.len() < lenx * leny {
+        return Vec::new();
+    }
+    let (dx, dy) = (
+        (end_x - start_x) / (lenx - 1) as f64,
+        (end_y - start_y) / (leny - 1) as f64,
+    );
+    let polar = |i: usize, j: usize| {
+        let (a, b) = data[i + lenx * j].to_options();
+        let (a, b) = (a.unwrap_or(0.0), b.unwrap_or(0.0));
+        (a.hypot(b), b.atan2(a))
+    };
+    let turn = |a: f64, b: f64| ((b - a + PI).rem_euclid(TAU) - PI).abs();
+    //p[1] to p[2] is a cut if its jump stands alone among p[0] to p[3] and the modulus is flat,
+    //which tells it apart from fast winding, and from zeros and poles where the modulus is not
+    let is_cut = |p: [(f64, f64); 4]| {
+        if turn(p[1].1, p[2].1) <= threshold
+            || turn(p[0].1, p[1].1) > threshold / 4.0
+            || turn(p[2].1, p[3].1) > threshold / 4.0
+        {
+            return false;
+        }
+        let (min, max) = p
+            .iter()
+            .fold((f64::INFINITY, 0.0f64), |(min, max), (m, _)| {
+                (min.min(*m), max.max(*m))
+            });
+        min > 0.0 && max.is_finite() && max < 1.5 * min
+    };
+    let x = |i: f64| start_x + i * dx;
+    let y = |j: f64| start_y + j * dy;
+    let row = |j: usize| {
+        let mut found = Vec::new();
+        for i in 0..lenx {
+            if i + 3 < lenx && is_cut([0, 1, 2, 3].map(|n| polar(i + n, j))) {
+                let u = x(i as f64 + 1.5);
+                found.push([
+                    Vec2::new(u, y(j as f64 - 0.5)),
+                    Vec2::new(u, y(j as f64 + 0.5)),
+                ])
+            }
+            if j + 3 < leny && is_cut([0, 1, 2, 3].map(|n| polar(i, j + n))) {
+                let t = y(j as f64 + 1.5);
+                found.push([
+                    Vec2::new(x(i as f64 - 0.5), t),
+                    Vec2::new(x(i as f64 + 0.5), t),
+                ])
+            }
+        }
+        found
+    };
+    #[cfg(feature = "rayon")]
+    let found = (0..leny).into_par_iter().flat_map_iter(row).collect();
+    #[cfg(not(feature = "rayon"))]
+    let found = (0..leny).flat_map(row).collect();
+    found
+}
+fn hsv2rgb(hue: f64, sat: f64, val: f64) -> [f64; 3] {
     if sat == 0.0 {
-        return rgb2val(val, val, val);
+        return [val, val, val];
     }
     let i = hue.floor();
     let f = hue.fract();
@@ -3626,12 +6430,12 @@ fn hsv2rgb(hue: f64, sat: f64, val: f64) -> [u8; 3] {
     let q = val * (1.0 - sat * f);
     let t = val * (1.0 - sat * (1.0 - f));
     match i as usize % 6 {
-        0 => rgb2val(val, t, p),
-        1 => rgb2val(q, val, p),
-        2 => rgb2val(p, val, t),
-        3 => rgb2val(p, q, val),
-        4 => rgb2val(t, p, val),
-        _ => rgb2val(val, p, q),
+        0 => [val, t, p],
+        1 => [q, val, p],
+        2 => [p, val, t],
+        3 => [p, q, val],
+        4 => [t, p, val],
+        _ => [val, p, q],
     }
 }
 fn rgb2val(r: f64, g: f64, b: f64) -> [u8; 3] {
@@ -3722,6 +6526,7 @@ fn shift_hue_by(color: &mut [f32; 3], diff: f32) {
     color[2] = c * new_hue.sin();
 }
 fn shift_hue(diff: f32, color: &Color) -> Color {
+    let alpha = color.a;
     let mut color = [
         color.r as f32 / 255.0,
         color.g as f32 / 255.0,
@@ -3730,10 +6535,11 @@ fn shift_hue(diff: f32, color: &Color) -> Color {
     rgb_to_oklch(&mut color);
     shift_hue_by(&mut color, diff);
     oklch_to_rgb(&mut color);
-    Color::new(
+    Color::rgba(
         (color[0] * 255.0) as u8,
         (color[1] * 255.0) as u8,
         (color[2] * 255.0) as u8,
+        alpha,
     )
 }
 fn line(
@@ -3765,6 +6571,21 @@ fn point(
         painter.rect_filled(point, &color, point_size)
     }
 }
+///seconds since the first call, for hosts which do not give InputState.time
+fn clock() -> Option<f64> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        static START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+        Some(
+            START
+                .get_or_init(std::time::Instant::now)
+                .elapsed()
+                .as_secs_f64(),
+        )
+    }
+    #[cfg(target_arch = "wasm32")]
+    None
+}
 #[cfg(feature = "serde")]
 pub(crate) fn update_saves(fd: &mut Vec<String>, n: &[(String, usize, String)]) {
     *fd = n
@@ -3797,3 +6618,156 @@ pub fn get_surface(width: i32, height: i32) -> skia_safe::Surface {
     )
     .unwrap()
 }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn decimation_follows_appends_past_capacity() {
+        let points = |r: std::ops::Range<usize>| {
+            r.map(|i| (i as f64, Complex::Real((i * i % 7) as f64)))
+                .collect::<Vec<_>>()
+        };
+        let mut graph = Graph::new(
+            Vec::new(),
+            vec![Name::new("f".to_string())],
+            false,
+            0.0,
+            100.0,
+        );
+        graph.screen = Vec2::new(10.0, 10.0);
+        graph.append_points(0, &points(0..100), Some(100));
+        graph.decimate_data();
+        let before = format!("{:?}", graph.decimated[0].as_ref().unwrap().1);
+        graph.append_points(0, &points(100..112), Some(100));
+        graph.decimate_data();
+        let after = format!("{:?}", graph.decimated[0].as_ref().unwrap().1);
+        assert_ne!(before, after);
+        let GraphData::Coord(data) = &graph.data[0] else {
+            unreachable!()
+        };
+        let expected = decimate(data.iter().copied(), graph.screen.x / 100.0, false);
+        assert_eq!(after, format!("{expected:?}"));
+    }
+    #[test]
+    fn bins_follow_data_modified() {
+        let mut graph = Graph::new(
+            vec![GraphData::Histogram(
+                vec![0.0, 1.0, 1.0, 2.0],
+                Bins::Count(2),
+            )],
+            vec![Name::new("f".to_string())],
+            false,
+            0.0,
+            2.0,
+        );
+        graph.screen = Vec2::new(10.0, 10.0);
+        graph.bin_data();
+        let before = graph.binned[0].as_ref().unwrap().counts.clone();
+        //same allocation and length, only the samples change
+        let GraphData::Histogram(data, _) = &mut graph.data[0] else {
+            unreachable!()
+        };
+        data.copy_from_slice(&[0.0, 0.0, 0.0, 2.0]);
+        graph.data_modified();
+        graph.bin_data();
+        let after = graph.binned[0].as_ref().unwrap().counts.clone();
+        assert_ne!(before, after);
+        assert_eq!(after.iter().sum::<usize>(), 4);
+    }
+    fn grid(
+        (lenx, leny): (usize, usize),
+        (start_x, start_y, end_x, end_y): (f64, f64, f64, f64),
+        f: impl Fn(f64, f64) -> Complex,
+    ) -> Vec<Complex> {
+        (0..lenx * leny)
+            .map(|i| {
+                let x = start_x + (i % lenx) as f64 * (end_x - start_x) / (lenx - 1) as f64;
+                let y = start_y + (i / lenx) as f64 * (end_y - start_y) / (leny - 1) as f64;
+                f(x, y)
+            })
+            .collect()
+    }
+    #[test]
+    fn contour_traces_a_circle() {
+        //a rectangular grid, so a wrong stride shows as a stretched circle
+        let (size, bounds) = ((61, 41), (-2.0, -1.5, 2.0, 1.5));
+        let data = grid(size, bounds, |x, y| Complex::Real(x * x + y * y - 1.0));
+        let segments = contour(&data, size, bounds);
+        assert!(segments.len() > 40);
+        for (a, b) in segments {
+            for p in [a, b] {
+                assert!((p.x.hypot(p.y) - 1.0).abs() < 0.01, "{p:?}")
+            }
+        }
+        assert!(contour(&data, (61, 0), bounds).is_empty());
+    }
+    #[test]
+    fn zeros_and_poles_on_a_rectangular_grid() {
+        let (size, bounds) = ((61, 41), (-2.0, -1.0, 2.0, 1.0));
+        //a zero at 1/2 and a double pole at -1/2
+        let data = grid(size, bounds, |x, y| {
+            let (a, b) = (x - 0.5, y);
+            let (c, d) = ((x + 0.5) * (x + 0.5) - y * y, 2.0 * (x + 0.5) * y);
+            let n = c * c + d * d;
+            Complex::Complex((a * c + b * d) / n, (b * c - a * d) / n)
+        });
+        let mut found = find_zeros_poles(3, &data, size, bounds);
+        found.sort_by_key(|z| z.order);
+        assert_eq!(found.len(), 2, "{found:?}");
+        let cell = 4.0 / 60.0;
+        for (z, (x, order)) in found.iter().zip([(-0.5, -2), (0.5, 1)]) {
+            assert_eq!((z.data, z.order), (3, order));
+            assert!((z.x - x).abs() < cell && z.y.abs() < cell, "{z:?}");
+        }
+    }
+    #[test]
+    fn fills_follow_the_fill_mode() {
+        let width = |f: fn(f64) -> f64| {
+            let data = (0..41)
+                .map(|i| Complex::Real(f(i as f64 / 10.0 - 2.0)))
+                .collect();
+            GraphData::Width(data, -2.0, 2.0)
+        };
+        let names = ["f", "g"].map(|n| Name::new(n.to_string()));
+        let mut graph = Graph::new(
+            vec![
+                width(|x| if x == 0.0 { f64::NAN } else { x * 0.5 }),
+                width(|x| x * 0.5 + 0.25),
+            ],
+            names.to_vec(),
+            false,
+            -2.0,
+            2.0,
+        );
+        graph.set_screen(100.0, 100.0, false, false);
+        let axis = graph.to_screen(0.0, 0.0).y;
+        let gap = graph.to_screen(0.0, 0.0).y - graph.to_screen(0.0, 0.25).y;
+        for (fill, bottom) in [
+            (Fill::Axis, Some(axis)),
+            (Fill::Above, Some(-2.0)),
+            (Fill::Below, Some(102.0)),
+            (Fill::Between("g".to_string()), None),
+        ] {
+            graph.names[0].fill = fill.clone();
+            let fills = graph.fills(&[false, false], false);
+            //the nan at x = 0 splits the fill in two
+            assert_eq!(fills.len(), 2, "{fill:?}");
+            for (k, top, b) in fills {
+                assert_eq!((k, top.len()), (0, b.len()));
+                for (t, b) in top.iter().zip(&b) {
+                    assert_eq!(t.x, b.x);
+                    match bottom {
+                        Some(y) => assert!((b.y - y).abs() < 1e-3, "{fill:?} {b:?}"),
+                        //edges past the screen are clamped to it
+                        None if t.y > 0.0 && b.y < 100.0 => {
+                            assert!((t.y - b.y - gap).abs() < 1e-3, "{t:?} {b:?}")
+                        }
+                        None => {}
+                    }
+                }
+            }
+        }
+        graph.names[0].fill = Fill::None;
+        assert!(graph.fills(&[false, false], false).is_empty());
+    }
+}
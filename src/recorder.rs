@@ -0,0 +1,213 @@
+use crate::types::*;
+use crate::ui::Painter;
+use std::f64::consts::TAU;
+impl Graph {
+    ///renders rec.frames frames without a window, before each one the view is changed by
+    ///rec.sweep and then frame(graph, n) is called, where the host can answer update_res,
+    ///returns one file for gif and apng or one png per frame for a png sequence,
+    ///the angle, slice, animation and screen are put back afterwards
+    pub fn record<F>(&mut self, rec: &Recording, mut frame: F) -> Result<Vec<Vec<u8>>, RecordError>
+    where
+        F: FnMut(&mut Graph, usize),
+    {
+        if rec.frames == 0 {
+            return Ok(Vec::new());
+        }
+        let max = if rec.format == RecordFormat::Gif {
+            u16::MAX as u32
+        } else {
+            i32::MAX as u32
+        };
+        if rec.width == 0 || rec.height == 0 || rec.width > max || rec.height > max {
+            return Err(RecordError::Size);
+        }
+        let (slice, slice_acc, var, animation) =
+            (self.slice, self.slice_acc, self.var, self.animation.clone());
+        let screen = (
+            self.angle,
+            self.offset,
+            self.screen,
+            self.screen_offset,
+            self.draw_offset,
+            self.side_bar_width,
+        );
+        let out = self.record_frames(rec, &mut frame);
+        (
+            self.angle,
+            self.offset,
+            self.screen,
+            self.screen_offset,
+            self.draw_offset,
+            self.side_bar_width,
+        ) = screen;
+        if self.animation != animation {
+            self.animation = animation;
+            self.apply_animation()
+        }
+        if (self.slice, self.var) != (slice, var) {
+            (self.slice, self.var) = (slice, var);
+            self.recalculate(None)
+        }
+        self.slice_acc = slice_acc;
+        self.partial_redraw = false;
+        self.cache = None;
+        out
+    }
+    fn record_frames<F>(
+        &mut self,
+        rec: &Recording,
+        frame: &mut F,
+    ) -> Result<Vec<Vec<u8>>, RecordError>
+    where
+        F: FnMut(&mut Graph, usize),
+    {
+        let angle = self.angle.x;
+        let (w, h) = (rec.width, rec.height);
+        let mut out = Vec::new();
+        match rec.format {
+            RecordFormat::Gif => {
+                let mut encoder = gif::Encoder::new(&mut out, w as u16, h as u16, &[])?;
+                encoder.set_repeat(gif::Repeat::Infinite)?;
+                let delay = (100.0 / rec.fps).round().max(2.0) as u16;
+                for n in 0..rec.frames {
+                    let mut rgba = self.record_frame(rec, n, angle, frame)?;
+                    //exact palette when a frame has at most 256 colors, which plots mostly do
+                    let mut gif = gif::Frame::from_rgba_speed(w as u16, h as u16, &mut rgba, 10);
+                    gif.delay = delay;
+                    encoder.write_frame(&gif)?;
+                }
+                encoder.into_inner()?;
+                Ok(vec![out])
+            }
+            RecordFormat::Apng => {
+                let mut encoder = png_encoder(&mut out, w, h);
+                encoder.set_animated(rec.frames as u32, 0)?;
+                let delay = (1000.0 / rec.fps).round().clamp(1.0, u16::MAX as f64) as u16;
+                encoder.set_frame_delay(delay, 1000)?;
+                let mut writer = encoder.write_header()?;
+                for n in 0..rec.frames {
+                    let rgba = self.record_frame(rec, n, angle, frame)?;
+                    writer.write_image_data(&rgba)?;
+                }
+                writer.finish()?;
+                Ok(vec![out])
+            }
+            RecordFormat::PngSequence => (0..rec.frames)
+                .map(|n| {
+                    let rgba = self.record_frame(rec, n, angle, frame)?;
+                    let mut out = Vec::new();
+                    let mut writer = png_encoder(&mut out, w, h).write_header()?;
+                    writer.write_image_data(&rgba)?;
+                    writer.finish()?;
+                    Ok(out)
+                })
+                .collect(),
+        }
+    }
+    fn record_frame<F>(
+        &mut self,
+        rec: &Recording,
+        n: usize,
+        angle: f64,
+        frame: &mut F,
+    ) -> Result<Vec<u8>, RecordError>
+    where
+        F: FnMut(&mut Graph, usize),
+    {
+        match rec.sweep {
+            Sweep::Rotate => {
+                self.angle.x = (angle + TAU * n as f64 / rec.frames as f64).rem_euclid(TAU)
+            }
+            Sweep::Slice(speed) if n > 0 => self.advance_slice(speed),
+            Sweep::Animation if n > 0 => self.step_animation(true),
+            Sweep::None | Sweep::Slice(_) | Sweep::Animation => {}
+        }
+        frame(self, n);
+        let rgba = self.render_rgba(rec.width, rec.height);
+        if rgba.len() != 4 * rec.width as usize * rec.height as usize {
+            return Err(RecordError::Render);
+        }
+        Ok(rgba)
+    }
+    #[cfg(feature = "skia")]
+    fn render_rgba(&mut self, width: u32, height: u32) -> Vec<u8> {
+        self.font_width();
+        self.set_screen(width as f64, height as f64, true, true);
+        let mut surface = crate::get_surface(width as i32, height as i32);
+        let mut painter = Painter::new(
+            &mut surface,
+            self.background_color,
+            self.anti_alias,
+            self.draw_offset,
+        );
+        let plot = |painter: &mut Painter, graph: &mut Graph| graph.plot(painter);
+        self.update_inner(&mut painter, plot, width as f64, height as f64);
+        painter.rgba()
+    }
+    #[cfg(feature = "tiny-skia")]
+    fn render_rgba(&mut self, width: u32, height: u32) -> Vec<u8> {
+        let Some(canvas) = tiny_skia::Pixmap::new(width, height) else {
+            return Vec::new();
+        };
+        self.font_width();
+        self.set_screen(width as f64, height as f64, true, true);
+        let mut painter = Painter::new(
+            self.background_color,
+            self.anti_alias,
+            self.draw_offset,
+            canvas,
+        );
+        let plot = |painter: &mut Painter, graph: &mut Graph| graph.plot(painter);
+        self.update_inner(&mut painter, plot, width as f64, height as f64);
+        painter.rgba()
+    }
+}
+fn png_encoder(out: &mut Vec<u8>, width: u32, height: u32) -> png::Encoder<'static, &mut Vec<u8>> {
+    let mut encoder = png::Encoder::new(out, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn gif_round_trip() {
+        let data = (0..64)
+            .map(|i| {
+                let x = i as f64 / 8.0 - 4.0;
+                Complex::Complex(x.sin(), x.cos())
+            })
+            .collect();
+        let mut graph = Graph::new(
+            vec![GraphData::Width(data, -4.0, 3.875)],
+            vec![],
+            true,
+            -4.0,
+            4.0,
+        );
+        graph.anti_alias = false;
+        let (w, h) = (96, 64);
+        let rec = Recording::new(w, h, 3, 10.0);
+        let out = graph.record(&rec, |_, _| {}).unwrap();
+        let expected = graph.render_rgba(w, h);
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(out[0].as_slice()).unwrap();
+        let mut frames = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!((frame.width, frame.height, frame.delay), (96, 64, 10));
+            //text edges take the frame past 256 colors, so it comes back quantized
+            assert_eq!(frame.buffer.len(), expected.len());
+            assert!(
+                frame
+                    .buffer
+                    .iter()
+                    .zip(expected.iter())
+                    .all(|(a, b)| a.abs_diff(*b) <= 48)
+            );
+            frames += 1
+        }
+        assert_eq!(frames, 3);
+    }
+}
@@ -173,26 +173,7 @@ impl Graph {
                 self.last_right_interact = None
             }
             if x < 0.0 && i.pointer.unwrap_or(false) {
-                if let Some(n) = self
-                    .blacklist_graphs
-                    .iter()
-                    .position(|&n| n == new as usize)
-                {
-                    self.blacklist_graphs.remove(n);
-                    if self.index_to_name(new as usize, true).0.is_some() {
-                        self.recalculate(Some(new as usize));
-                    } else {
-                        self.name_modified(Some(n));
-                    }
-                } else if let (Some(i), _) = self.index_to_name(new as usize, true) {
-                    if !matches!(self.names[i].show, Show::None) {
-                        self.blacklist_graphs.push(new as usize);
-                        self.recalculate(Some(new as usize));
-                    }
-                } else {
-                    self.blacklist_graphs.push(new as usize);
-                    self.name_modified(Some(new as usize));
-                }
+                self.toggle_graph(new as usize)
             }
         }
         if !stop_keybinds {
@@ -527,6 +508,9 @@ impl Graph {
                                                 name: String::new(),
                                                 vars: v,
                                                 show: Show::None,
+                                                fill: Fill::None,
+                                                style: Style::default(),
+                                                secondary: false,
                                             })
                                         }
                                         down(self, &mut text_box);
@@ -537,6 +521,9 @@ impl Graph {
                                             name: self.names[i].vars.remove(j),
                                             vars: self.names[i].vars.drain(..j).collect(),
                                             show: Show::None,
+                                            fill: Fill::None,
+                                            style: Style::default(),
+                                            secondary: false,
                                         };
                                         self.names.insert(i, name);
                                     }
@@ -692,7 +679,23 @@ impl Graph {
         T: Fn(&mut Graph, &mut (usize, usize), String),
     {
         let do_rev = |r: bool| -> bool { if rev { r } else { !r } };
-        let s = std::mem::replace(&mut self.history[i], Change::None);
+        let mut s = std::mem::replace(&mut self.history[i], Change::None);
+        if let Change::Look(n, fill, style, secondary) = &mut s {
+            //swapping back and forth serves both undo and redo
+            if let Some(name) = self.names.get_mut(*n) {
+                std::mem::swap(&mut name.fill, fill);
+                std::mem::swap(&mut name.style, style);
+                std::mem::swap(&mut name.secondary, secondary);
+            }
+            self.history[i] = s;
+            self.recalculate(None);
+            return;
+        }
+        if let Change::Animate(a) = &mut s {
+            std::mem::swap(&mut self.animation, a);
+            self.history[i] = s;
+            return;
+        }
         match &s {
             &Change::Char((a, b), _, r) if do_rev(r) => {
                 self.remove_char(b, a);
@@ -723,7 +726,7 @@ impl Graph {
                 self.insert_name(b, var);
                 *text_box = (0, b);
             }
-            Change::None => unreachable!(),
+            Change::Look(..) | Change::Animate(_) | Change::None => unreachable!(),
         }
         self.history[i] = s;
     }
@@ -1022,6 +1025,9 @@ impl Graph {
                         vars: if var { vec![String::new()] } else { Vec::new() },
                         name: String::new(),
                         show: Show::None,
+                        fill: Fill::None,
+                        style: Style::default(),
+                        secondary: false,
                     })
                 } else {
                     let mut i = j;
@@ -1041,6 +1047,9 @@ impl Graph {
                                         vars: Vec::new(),
                                         name: String::new(),
                                         show: Show::None,
+                                        fill: Fill::None,
+                                        style: Style::default(),
+                                        secondary: false,
                                     },
                                 );
                             }
@@ -1058,6 +1067,33 @@ impl Graph {
             Menu::Settings => todo!(),
         }
     }
+    ///hides or shows the graph or var on the given line of the side bar
+    pub(crate) fn toggle_graph(&mut self, line: usize) {
+        if let Some(n) = self.blacklist_graphs.iter().position(|&n| n == line) {
+            self.blacklist_graphs.remove(n);
+            if self.index_to_name(line, true).0.is_some() {
+                self.recalculate(Some(line));
+            } else {
+                self.name_modified(Some(n));
+            }
+        } else if let (Some(i), _) = self.index_to_name(line, true) {
+            if !matches!(self.names[i].show, Show::None) {
+                self.blacklist_graphs.push(line);
+                self.recalculate(Some(line));
+            }
+        } else {
+            self.blacklist_graphs.push(line);
+            self.name_modified(Some(line));
+        }
+    }
+    ///side bar line of the given name
+    pub(crate) fn name_to_index(&self, name: usize) -> usize {
+        self.names[..name]
+            .iter()
+            .map(|n| n.vars.len() + 1)
+            .sum::<usize>()
+            + self.names[name].vars.len()
+    }
     pub fn index_to_name(
         &self,
         mut i: usize,
@@ -1117,6 +1153,61 @@ impl Graph {
             Menu::Settings => todo!(),
         }
     }
+    ///shortcuts which change how the function under the side bar cursor is drawn
+    pub(crate) fn keybinds_line(&mut self, i: &InputState, keybinds: &Keybinds) {
+        if i.keys_pressed(keybinds.fill) {
+            self.restyle(|g, n| g.names[n].fill = g.names[n].fill.cycle(&g.names, n))
+        }
+        if i.keys_pressed(keybinds.connect) {
+            self.restyle(|g, n| g.names[n].style.connect = g.names[n].style.connect.cycle())
+        }
+        if i.keys_pressed(keybinds.marker) {
+            self.restyle(|g, n| {
+                let lines = g.lines;
+                g.names[n].style.cycle_marker(lines)
+            })
+        }
+        if i.keys_pressed(keybinds.dash) {
+            self.restyle(|g, n| g.names[n].style.dash = g.names[n].style.dash.cycle())
+        }
+        if i.keys_pressed(keybinds.secondary) {
+            self.restyle(|g, n| {
+                g.names[n].secondary = !g.names[n].secondary;
+                g.recalculate(None);
+                g.name_modified(None)
+            })
+        }
+        if i.keys_pressed(keybinds.animate)
+            && let Some((_, b)) = self.text_box
+        {
+            let old = self.animation.clone();
+            self.animate_line(b);
+            if self.animation != old {
+                self.history_push(Change::Animate(old))
+            }
+        }
+    }
+    ///applies f to the name under the side bar cursor, undoable like any edit
+    fn restyle<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut Graph, usize),
+    {
+        let Some((_, b)) = self.text_box else {
+            return;
+        };
+        let (Some(n), _) = self.index_to_name(b, true) else {
+            return;
+        };
+        let name = &self.names[n];
+        self.history_push(Change::Look(
+            n,
+            name.fill.clone(),
+            name.style,
+            name.secondary,
+        ));
+        self.partial_redraw = false;
+        f(self, n)
+    }
     pub(crate) fn history_push(&mut self, c: Change) {
         if !matches!(self.menu, Menu::Side) {
             return;
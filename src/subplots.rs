@@ -0,0 +1,256 @@
+use crate::types::*;
+use crate::ui::Painter;
+impl Subplots {
+    ///creates a grid of panes, filled row by row with cols panes per row
+    pub fn new(panes: Vec<Graph>, cols: usize) -> Self {
+        Self {
+            panes,
+            cols: cols.max(1),
+            active: 0,
+            size: Vec2::splat(0.0),
+        }
+    }
+    ///how many rows the grid has
+    pub fn rows(&self) -> usize {
+        self.panes.len().div_ceil(self.cols.max(1))
+    }
+    ///a pane with an open menu takes over the whole surface until closed
+    fn focused(&self) -> Option<usize> {
+        self.panes
+            .iter()
+            .position(|p| !matches!(p.menu, Menu::Normal))
+    }
+    ///top left corner and size of a pane when drawn into a width by height surface
+    pub fn pane_rect(&self, n: usize, width: f64, height: f64) -> (Pos, Vec2) {
+        if self.focused().is_some() {
+            return (Pos::new(0.0, 0.0), Vec2::new(width, height));
+        }
+        let (cols, rows) = (self.cols.max(1), self.rows().max(1));
+        let (col, row) = (n % cols, n / cols);
+        let x = |c: usize| (c as f64 * width / cols as f64).floor();
+        let y = |r: usize| (r as f64 * height / rows as f64).floor();
+        (
+            Pos::new(x(col) as f32, y(row) as f32),
+            Vec2::new(x(col + 1) - x(col), y(row + 1) - y(row)),
+        )
+    }
+    ///which pane is under a point of the last drawn surface
+    pub fn pane_at(&self, p: Vec2) -> Option<usize> {
+        if let Some(n) = self.focused() {
+            return Some(n);
+        }
+        (0..self.panes.len()).find(|n| {
+            let (o, s) = self.pane_rect(*n, self.size.x, self.size.y);
+            let o = o.to_vec();
+            p.x >= o.x && p.y >= o.y && p.x < o.x + s.x && p.y < o.y + s.y
+        })
+    }
+    ///like Graph::update_res but for every pane, tagged with the pane index
+    pub fn update_res(&mut self) -> Vec<(usize, Bound, Option<usize>)> {
+        self.panes
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(n, p)| p.update_res().map(|(b, k)| (n, b, k)))
+            .collect()
+    }
+    ///like Graph::update_res_name but for every pane, tagged with the pane index
+    pub fn update_res_name(&self) -> Vec<(usize, &[Name])> {
+        self.panes
+            .iter()
+            .enumerate()
+            .filter_map(|(n, p)| p.update_res_name().map(|names| (n, names)))
+            .collect()
+    }
+    #[cfg(any(feature = "skia", feature = "tiny-skia", feature = "wasm-draw"))]
+    ///if any pane wants to be redrawn
+    pub fn request_redraw(&self) -> bool {
+        self.panes.iter().any(|p| p.request_redraw)
+    }
+    #[cfg(any(feature = "skia", feature = "tiny-skia", feature = "wasm-draw"))]
+    ///is cursor dragging a value in any pane
+    pub fn is_drag(&self) -> bool {
+        self.panes.iter().any(|p| p.is_drag())
+    }
+    ///sends input to the pane under the cursor, held buttons stay with the pane they started in
+    fn keybinds_inner(&mut self, i: &InputState) -> usize {
+        let held = matches!(i.pointer, Some(false)) || matches!(i.pointer_right, Some(false));
+        let n = if let Some(n) = self.focused() {
+            n
+        } else if held {
+            self.active
+        } else {
+            i.pointer_pos
+                .and_then(|p| self.pane_at(p))
+                .unwrap_or(self.active)
+        };
+        if n != self.active
+            && let Some(pane) = self.panes.get_mut(self.active)
+        {
+            pane.mouse_position = None;
+            pane.partial_redraw = false;
+        }
+        self.active = n;
+        if let Some(pane) = self.panes.get_mut(n) {
+            pane.keybinds_inner(i)
+        }
+        n
+    }
+    #[cfg(feature = "egui")]
+    ///process the current keys and mouse/touch inputs for the pane under the cursor,
+    ///expected to run before update_res()
+    pub fn keybinds(&mut self, ui: &mut egui::Ui) {
+        let n = ui.input(|i| self.keybinds_inner(&i.into()));
+        if let Some(s) = self.panes.get(n).and_then(|p| p.clipboard.as_ref())
+            && !s.0.is_empty()
+        {
+            ui.ctx().copy_text(s.0.clone())
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(any(feature = "skia", feature = "tiny-skia", feature = "wasm-draw"))]
+    ///process the current keys and mouse/touch inputs for the pane under the cursor,
+    ///expected to run before update_res()
+    pub fn keybinds(&mut self, i: &InputState) {
+        self.keybinds_inner(i);
+    }
+    #[cfg(target_arch = "wasm32")]
+    #[cfg(any(feature = "skia", feature = "tiny-skia", feature = "wasm-draw"))]
+    ///process the current keys and mouse/touch inputs for the pane under the cursor,
+    ///expected to run before update_res()
+    pub fn keybinds(&mut self, i: &InputState) {
+        let n = self.keybinds_inner(i);
+        if let Some(s) = self.panes.get(n).and_then(|p| p.clipboard.as_ref()) {
+            crate::ui::write_clipboard(&s.0);
+        }
+    }
+    fn draw<F>(&mut self, painter: &mut Painter, plot: F, width: f64, height: f64)
+    where
+        F: Fn(&mut Painter, &mut Graph) -> Option<Vec<(f32, Draw, Color)>>,
+    {
+        self.size = Vec2::new(width, height);
+        let focused = self.focused();
+        for n in 0..self.panes.len() {
+            if focused.is_some_and(|f| f != n) {
+                continue;
+            }
+            let (origin, size) = self.pane_rect(n, width, height);
+            self.panes[n].draw_pane(painter, &plot, origin, size.x, size.y);
+        }
+        painter.offset = Pos::new(0.0, 0.0);
+        let Some(color) = self.panes.first().map(|p| p.axis_color) else {
+            return;
+        };
+        if focused.is_none() {
+            //grid cells without a pane
+            let background = self.background().0;
+            for n in self.panes.len()..self.rows() * self.cols {
+                let (origin, size) = self.pane_rect(n, width, height);
+                let end = Pos::new(origin.x + size.x as f32, origin.y + size.y as f32);
+                painter.fill_rect(origin, end, &background);
+            }
+            for c in 1..self.cols.min(self.panes.len()) {
+                let x = self.pane_rect(c, width, height).0.x - 1.0;
+                painter.line_segment([Pos::new(x, 0.0), Pos::new(x, height as f32)], 1.0, &color);
+            }
+            for r in 1..self.rows() {
+                let y = self.pane_rect(r * self.cols, width, height).0.y - 1.0;
+                painter.line_segment([Pos::new(0.0, y), Pos::new(width as f32, y)], 1.0, &color);
+            }
+        }
+    }
+    fn background(&self) -> (Color, bool) {
+        self.panes.first().map_or((Color::splat(255), true), |p| {
+            (p.background_color, p.anti_alias)
+        })
+    }
+    #[cfg(feature = "egui")]
+    ///repaints every pane
+    pub fn update(&mut self, ui: &egui::Ui) {
+        let rect = ui.available_size();
+        for pane in &mut self.panes {
+            pane.font_width(ui);
+        }
+        let mut painter = Painter::new(ui, Pos::new(0.0, 0.0));
+        let plot = |painter: &mut Painter, graph: &mut Graph| graph.plot(painter, ui);
+        self.draw(&mut painter, plot, rect.x as f64, rect.y as f64);
+    }
+    #[cfg(feature = "skia")]
+    #[cfg(not(feature = "skia-vulkan"))]
+    ///repaints every pane
+    pub fn update<T>(&mut self, width: u32, height: u32, _buffer: &mut T)
+    where
+        T: std::ops::DerefMut<Target = [u32]>,
+    {
+        let mut surface = crate::get_surface(width as i32, height as i32);
+        let (background, anti_alias) = self.background();
+        let mut painter = Painter::new(&mut surface, background, anti_alias, Pos::new(0.0, 0.0));
+        for pane in &mut self.panes {
+            pane.font_width();
+        }
+        let plot = |painter: &mut Painter, graph: &mut Graph| graph.plot(painter);
+        self.draw(&mut painter, plot, width as f64, height as f64);
+        #[cfg(not(target_arch = "wasm32"))]
+        painter.save(_buffer);
+    }
+    #[cfg(feature = "skia")]
+    ///get png data of every pane
+    pub fn get_png(&mut self, width: u32, height: u32) -> crate::ui::Data {
+        let mut surface = crate::get_surface(width as i32, height as i32);
+        let (background, anti_alias) = self.background();
+        let mut painter = Painter::new(&mut surface, background, anti_alias, Pos::new(0.0, 0.0));
+        for pane in &mut self.panes {
+            pane.font_width();
+        }
+        let plot = |painter: &mut Painter, graph: &mut Graph| graph.plot(painter);
+        self.draw(&mut painter, plot, width as f64, height as f64);
+        painter.save_img(
+            &self
+                .panes
+                .first()
+                .map(|p| p.image_format)
+                .unwrap_or_default(),
+        )
+    }
+    #[cfg(feature = "tiny-skia")]
+    ///repaints every pane
+    pub fn update<T>(&mut self, width: u32, height: u32, _buffer: &mut T)
+    where
+        T: std::ops::DerefMut<Target = [u32]>,
+    {
+        let canvas = tiny_skia::Pixmap::new(width, height).unwrap();
+        let (background, anti_alias) = self.background();
+        let mut painter = Painter::new(background, anti_alias, Pos::new(0.0, 0.0), canvas);
+        for pane in &mut self.panes {
+            pane.font_width();
+        }
+        let plot = |painter: &mut Painter, graph: &mut Graph| graph.plot(painter);
+        self.draw(&mut painter, plot, width as f64, height as f64);
+        #[cfg(not(target_arch = "wasm32"))]
+        painter.save(_buffer);
+    }
+    #[cfg(feature = "tiny-skia-png")]
+    ///get png data of every pane
+    pub fn get_png(&mut self, width: u32, height: u32) -> crate::ui::Data {
+        let canvas = tiny_skia::Pixmap::new(width, height).unwrap();
+        let (background, anti_alias) = self.background();
+        let mut painter = Painter::new(background, anti_alias, Pos::new(0.0, 0.0), canvas);
+        for pane in &mut self.panes {
+            pane.font_width();
+        }
+        let plot = |painter: &mut Painter, graph: &mut Graph| graph.plot(painter);
+        self.draw(&mut painter, plot, width as f64, height as f64);
+        let data = painter.save_png();
+        crate::ui::Data { data }
+    }
+    #[cfg(feature = "wasm-draw")]
+    ///repaints every pane
+    pub fn update(&mut self, width: u32, height: u32) {
+        let (background, anti_alias) = self.background();
+        let mut painter = Painter::new(background, anti_alias, Pos::new(0.0, 0.0));
+        for pane in &mut self.panes {
+            pane.font_width();
+        }
+        let plot = |painter: &mut Painter, graph: &mut Graph| graph.plot(painter);
+        self.draw(&mut painter, plot, width as f64, height as f64);
+    }
+}
@@ -28,6 +28,15 @@ pub enum GraphMode {
     Polar,
     ///takes a slice of a 3d function and applys polar logic
     SlicePolar,
+    ///draws the curve where the real part of a 3d data set is zero in 2d,
+    ///data is requested at screen resolution like domain coloring
+    Implicit,
+    ///stereographically maps the complex plane onto a sphere in 3d and colors it like domain coloring,
+    ///data is requested with Bound::Sphere
+    RiemannSphere,
+    ///draws the images of a grid of horizontal and vertical lines of the Graph.var square,
+    ///real on the x axis and imaginary on the y axis, data is requested with Bound::Grid
+    ConformalGrid,
 }
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug)]
@@ -38,13 +47,13 @@ pub enum GraphData {
     ///each complex number is mapped to the first element in the tuple on the x axis
     Coord(Vec<(f64, Complex)>),
     ///3d data set where the first 2 floats are the starting x/y positions and the last 2 floats are
-    ///the ending x/y positions,
+    ///the ending x/y positions, and the usize is the amount of elements along the x axis
     ///
     ///the ith element in the vector corrosponds to the (i % len)th element down the x axis
     ///and the (i / len)th element down the y axis
     ///
-    ///expects square vector size
-    Width3D(Vec<Complex>, f64, f64, f64, f64),
+    ///if the usize is none expects square vector size
+    Width3D(Vec<Complex>, f64, f64, f64, f64, Option<usize>),
     ///each complex number is mapped to the first element in the tuple on the x axis
     ///and the second element in the tuple on the y axis
     Coord3D(Vec<(f64, f64, Complex)>),
@@ -54,32 +63,79 @@ pub enum GraphData {
     Point(Vec2),
     ///a list of graphs, so that all graphs will be the same color
     List(Vec<GraphData>),
+    ///bars given as (x, height, width) where x is the center of the bar, 2d only
+    Bars(Vec<(f64, f64, f64)>),
+    ///raw samples which get binned into a histogram, 2d only
+    Histogram(Vec<f64>, Bins),
+    ///like Coord but each point also has an x and y error given as (x, y, x error, y error),
+    ///errors are drawn as whiskers and an error of 0 draws none, 2d only
+    CoordErr(Vec<(f64, Complex, f64, f64)>),
+    ///a translucent band given as (x, lower, upper), 2d only
+    Band(Vec<(f64, f64, f64)>),
     None,
 }
 impl GraphData {
+    ///the real part of a 2d data set as (x, y), non real values are nan
+    pub(crate) fn real_samples(&self) -> Option<Vec<(f64, f64)>> {
+        match self {
+            GraphData::Width(data, start, end) => Some(
+                data.iter()
+                    .enumerate()
+                    .map(|(i, y)| {
+                        let x = (i as f64 / (data.len() - 1).max(1) as f64 - 0.5) * (end - start)
+                            + (start + end) * 0.5;
+                        (x, y.to_options().0.unwrap_or(f64::NAN))
+                    })
+                    .collect(),
+            ),
+            GraphData::Coord(data) => Some(
+                data.iter()
+                    .map(|(x, y)| (*x, y.to_options().0.unwrap_or(f64::NAN)))
+                    .collect(),
+            ),
+            GraphData::CoordErr(data) => Some(
+                data.iter()
+                    .map(|(x, y, _, _)| (*x, y.to_options().0.unwrap_or(f64::NAN)))
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
     pub fn set_type(&mut self, ty: GraphType, cap: usize) {
         match (self, ty) {
             (GraphData::Width(v, _, _), GraphType::Width) => v.reserve(cap.saturating_sub(v.len())),
             (GraphData::Coord(v), GraphType::Coord) => v.reserve(cap.saturating_sub(v.len())),
-            (GraphData::Width3D(v, _, _, _, _), GraphType::Width3D) => {
+            (GraphData::Width3D(v, _, _, _, _, _), GraphType::Width3D) => {
                 v.reserve(cap.saturating_sub(v.len()))
             }
             (GraphData::Coord3D(v), GraphType::Coord3D) => v.reserve(cap.saturating_sub(v.len())),
             (GraphData::Constant(_, _), GraphType::Constant) => {}
             (GraphData::Point(_), GraphType::Point) => {}
             (GraphData::List(_), GraphType::List) => {}
+            (GraphData::Bars(v), GraphType::Bars) => v.reserve(cap.saturating_sub(v.len())),
+            (GraphData::Histogram(v, _), GraphType::Histogram) => {
+                v.reserve(cap.saturating_sub(v.len()))
+            }
+            (GraphData::CoordErr(v), GraphType::CoordErr) => v.reserve(cap.saturating_sub(v.len())),
+            (GraphData::Band(v), GraphType::Band) => v.reserve(cap.saturating_sub(v.len())),
             (GraphData::None, GraphType::None) => {}
             (s, ty) => {
                 *s = match ty {
                     GraphType::Width => GraphData::Width(Vec::with_capacity(cap), 0.0, 0.0),
                     GraphType::Coord => GraphData::Coord(Vec::with_capacity(cap)),
                     GraphType::Width3D => {
-                        GraphData::Width3D(Vec::with_capacity(cap), 0.0, 0.0, 0.0, 0.0)
+                        GraphData::Width3D(Vec::with_capacity(cap), 0.0, 0.0, 0.0, 0.0, None)
                     }
                     GraphType::Coord3D => GraphData::Coord3D(Vec::with_capacity(cap)),
                     GraphType::Constant => GraphData::Constant(Complex::Real(0.0), false),
                     GraphType::Point => GraphData::Point(Vec2::splat(0.0)),
                     GraphType::List => GraphData::List(Vec::with_capacity(cap)),
+                    GraphType::Bars => GraphData::Bars(Vec::with_capacity(cap)),
+                    GraphType::Histogram => {
+                        GraphData::Histogram(Vec::with_capacity(cap), Bins::Auto)
+                    }
+                    GraphType::CoordErr => GraphData::CoordErr(Vec::with_capacity(cap)),
+                    GraphType::Band => GraphData::Band(Vec::with_capacity(cap)),
                     GraphType::None => GraphData::None,
                 }
             }
@@ -96,9 +152,57 @@ pub enum GraphType {
     Constant,
     Point,
     List,
+    Bars,
+    Histogram,
+    CoordErr,
+    Band,
     None,
 }
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Bins {
+    ///bin count is picked from the amount of samples
+    #[default]
+    Auto,
+    ///fixed amount of bins spread over the range of the samples
+    Count(usize),
+    ///fixed bin width starting at the smallest sample
+    Width(f64),
+}
+impl Bins {
+    ///returns the start of the first bin, the width of each bin, and the count in each bin,
+    ///non finite samples are ignored, at most max bins are made and wider bins are used past that
+    pub fn bin(self, data: &[f64], max: usize) -> (f64, f64, Vec<usize>) {
+        let limit = max.max(1);
+        let (min, max) = data
+            .iter()
+            .filter(|x| x.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), x| {
+                (a.min(*x), b.max(*x))
+            });
+        if !min.is_finite() {
+            return (0.0, 0.0, Vec::new());
+        }
+        let range = max - min;
+        let (len, width) = match self {
+            Bins::Width(w) if w > 0.0 && range / w < limit as f64 => ((range / w) as usize + 1, w),
+            _ => {
+                let len = match self {
+                    Bins::Count(n) => n.clamp(1, limit),
+                    Bins::Width(w) if w > 0.0 => limit,
+                    _ => ((data.len() as f64).log2().ceil() as usize + 1).min(limit),
+                };
+                (len, if range > 0.0 { range / len as f64 } else { 1.0 })
+            }
+        };
+        let mut bins = vec![0; len];
+        for x in data.iter().filter(|x| x.is_finite()) {
+            bins[(((x - min) / width) as usize).min(len - 1)] += 1;
+        }
+        (min, width, bins)
+    }
+}
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug)]
 pub struct Name {
     pub vars: Vec<String>,
@@ -106,6 +210,15 @@ pub struct Name {
     pub name: String,
     ///if the function has an imaginary part or not
     pub show: Show,
+    ///what region of the function gets shaded
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fill: Fill,
+    ///how the points of the function are drawn
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub style: Style,
+    ///if the function is drawn against the secondary y axis
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub secondary: bool,
 }
 impl Name {
     pub fn new(name: String) -> Self {
@@ -113,9 +226,239 @@ impl Name {
             vars: Vec::new(),
             name,
             show: Show::Real,
+            fill: Fill::None,
+            style: Style::default(),
+            secondary: false,
+        }
+    }
+}
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, Default, PartialEq)]
+pub struct Style {
+    ///overrides Graph::lines for this function
+    pub lines: Option<Lines>,
+    ///how consecutive points are joined
+    pub connect: Connect,
+    ///shape drawn at each point when points are shown
+    pub marker: Marker,
+    ///overrides Graph::line_width for this function
+    pub line_width: Option<f32>,
+    ///dash pattern of the lines
+    pub dash: Dash,
+}
+impl Style {
+    ///if this draws nothing but straight lines given the global setting
+    pub(crate) fn plain(&self, lines: Lines) -> bool {
+        self.connect == Connect::Line && matches!(self.lines.unwrap_or(lines), Lines::Lines)
+    }
+    ///steps through each marker shape, then back to no markers
+    pub(crate) fn cycle_marker(&mut self, lines: Lines) {
+        let lines = self.lines.unwrap_or(lines);
+        if matches!(lines, Lines::Lines) {
+            self.lines = Some(Lines::LinesPoints);
+            self.marker = Marker::Square
+        } else if self.marker == Marker::Diamond {
+            self.lines = Some(if matches!(lines, Lines::Points) {
+                Lines::Points
+            } else {
+                Lines::Lines
+            });
+            self.marker = Marker::Square
+        } else {
+            self.marker = match self.marker {
+                Marker::Square => Marker::Circle,
+                Marker::Circle => Marker::Cross,
+                Marker::Cross => Marker::Triangle,
+                _ => Marker::Diamond,
+            }
+        }
+    }
+}
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, Default, PartialEq)]
+pub enum Connect {
+    ///straight lines between points
+    #[default]
+    Line,
+    ///vertical then horizontal, each step takes the value of the next point
+    Pre,
+    ///horizontal then vertical, each step takes the value of the previous point
+    Post,
+    ///steps halfway between points
+    Mid,
+    ///a vertical line from the x axis to each point
+    Stem,
+}
+impl Connect {
+    pub(crate) fn cycle(&self) -> Self {
+        match self {
+            Connect::Line => Connect::Pre,
+            Connect::Pre => Connect::Post,
+            Connect::Post => Connect::Mid,
+            Connect::Mid => Connect::Stem,
+            Connect::Stem => Connect::Line,
+        }
+    }
+}
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, Default, PartialEq)]
+pub enum Dash {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+    DashDot,
+}
+impl Dash {
+    ///alternating on and off lengths, in multiples of the line width
+    pub(crate) fn pattern(&self) -> &'static [f32] {
+        match self {
+            Dash::Solid => &[],
+            Dash::Dashed => &[4.0, 2.0],
+            Dash::Dotted => &[1.0, 1.5],
+            Dash::DashDot => &[4.0, 2.0, 1.0, 2.0],
+        }
+    }
+    pub(crate) fn cycle(&self) -> Self {
+        match self {
+            Dash::Solid => Dash::Dashed,
+            Dash::Dashed => Dash::Dotted,
+            Dash::Dotted => Dash::DashDot,
+            Dash::DashDot => Dash::Solid,
         }
     }
 }
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, Default, PartialEq)]
+pub enum Marker {
+    #[default]
+    Square,
+    Circle,
+    Cross,
+    Triangle,
+    Diamond,
+}
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct Annotation {
+    pub text: String,
+    ///data coordinates of the text, z is only used in 3d
+    pub pos: Vec3,
+    ///which corner or edge of the text sits on pos
+    pub align: Align,
+    ///data coordinates of what an arrow from pos points to
+    pub arrow: Option<Vec3>,
+    ///color of the text and arrow, the text color if None
+    pub color: Option<Color>,
+}
+impl Annotation {
+    pub fn new(text: String, x: f64, y: f64) -> Self {
+        Annotation {
+            text,
+            pos: Vec3::new(x, y, 0.0),
+            align: Align::LeftBottom,
+            arrow: None,
+            color: None,
+        }
+    }
+}
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Fill {
+    #[default]
+    None,
+    ///fill between the function and the x axis
+    Axis,
+    ///fill between the function and the function with the given name
+    Between(String),
+    ///fill everything above the function, for y > f(x)
+    Above,
+    ///fill everything below the function, for y < f(x)
+    Below,
+}
+impl Fill {
+    ///the next fill mode, cycling through every other named function for Fill::Between
+    pub(crate) fn cycle(&self, names: &[Name], own: usize) -> Self {
+        let mut others = names
+            .iter()
+            .enumerate()
+            .filter(|(i, n)| *i != own && !n.name.is_empty())
+            .map(|(_, n)| n.name.as_str());
+        match self {
+            Fill::None => Fill::Axis,
+            Fill::Axis => Fill::Above,
+            Fill::Above => Fill::Below,
+            Fill::Below => others
+                .next()
+                .map_or(Fill::None, |n| Fill::Between(n.to_string())),
+            Fill::Between(name) => others
+                .skip_while(|n| n != name)
+                .nth(1)
+                .map_or(Fill::None, |n| Fill::Between(n.to_string())),
+        }
+    }
+}
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, Default, PartialEq)]
+pub enum LegendPos {
+    #[default]
+    TopRight,
+    TopLeft,
+    BottomLeft,
+    BottomRight,
+    ///in a column to the right of the plot, shrinking the plot to fit
+    Outside,
+    ///top left corner of the legend as a fraction of the screen, set by dragging the legend
+    Free(Vec2),
+}
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Legend {
+    pub pos: LegendPos,
+    ///draw a translucent box behind the legend
+    pub background: bool,
+    ///entries per column before starting a new one, 0 to fill the height of the screen
+    pub rows: usize,
+}
+impl Default for Legend {
+    fn default() -> Self {
+        Legend {
+            pos: LegendPos::TopRight,
+            background: true,
+            rows: 0,
+        }
+    }
+}
+pub(crate) struct LegendEntry {
+    ///index into names
+    pub(crate) name: usize,
+    ///index into data
+    pub(crate) data: usize,
+    pub(crate) label: String,
+    pub(crate) color: Color,
+    pub(crate) hidden: bool,
+    ///top left of the row
+    pub(crate) pos: Pos,
+}
+///bins of a histogram data set, kept while the samples and bin settings stay the same
+pub(crate) struct Binned {
+    pub(crate) generation: u64,
+    pub(crate) len: usize,
+    pub(crate) bins: Bins,
+    pub(crate) max: usize,
+    pub(crate) start: f64,
+    pub(crate) width: f64,
+    pub(crate) counts: Vec<usize>,
+}
+#[derive(Copy, Clone, PartialEq)]
+pub(crate) struct Decimation {
+    pub(crate) generation: u64,
+    pub(crate) len: usize,
+    pub(crate) start: f64,
+    pub(crate) end: f64,
+    pub(crate) scale: f64,
+    pub(crate) only_real: bool,
+}
 #[derive(Copy, Clone)]
 pub(crate) enum Draw {
     Line(Pos, Pos, f32),
@@ -136,6 +479,14 @@ pub enum Bound {
     Width(f64, f64, Prec),
     ///a 3d data set is requested
     Width3D(f64, f64, f64, f64, Prec),
+    ///a 3d data set over longitude and latitude of the riemann sphere is requested, laid out like Width3D
+    ///with the input at each sample given by Complex::from_sphere(longitude, latitude)
+    Sphere(f64, f64, f64, f64, Prec),
+    ///images of lines are requested from the start x/y to the end x/y, with Prec::Dimension giving
+    ///the amount of lines per direction and the samples per line,
+    ///expects a List of the horizontal lines from the start y to the end y
+    ///followed by the vertical lines from the start x to the end x, each a Width or Coord of the values
+    Grid(f64, f64, f64, f64, Prec),
 }
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Copy, Debug, Default)]
@@ -155,13 +506,258 @@ impl Show {
     }
 }
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Clone, Debug, Copy, Default)]
+#[derive(Clone, Debug, Copy, Default, PartialEq)]
 pub enum Lines {
     Points,
     LinesPoints,
     #[default]
     Lines,
 }
+///how domain coloring maps a value to a color, the hue always follows the argument
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, Default, PartialEq)]
+pub enum DomainColorScheme {
+    ///contours of the real and imaginary parts, or of the modulus with Graph.domain_alternate
+    #[default]
+    Default,
+    ///classic hsv, zeros are black and poles are white
+    Hsv,
+    ///phase portrait shaded by the log modulus and by argument sectors
+    Enhanced,
+    ///oklch with lightness from the modulus, so hues are perceptually even
+    Oklch,
+    ///checkerboard over a polar grid of log modulus and argument
+    Checkerboard,
+}
+impl DomainColorScheme {
+    pub(crate) fn cycle(self) -> Self {
+        match self {
+            Self::Default => Self::Hsv,
+            Self::Hsv => Self::Enhanced,
+            Self::Enhanced => Self::Oklch,
+            Self::Oklch => Self::Checkerboard,
+            Self::Checkerboard => Self::Default,
+        }
+    }
+}
+///what colors curves along Graph.gradient_colors in flatten and depth mode
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, Default, PartialEq)]
+pub enum Gradient {
+    ///one color per curve from Graph.main_colors
+    #[default]
+    None,
+    ///the input variable
+    Parameter,
+    ///the modulus of the output
+    Modulus,
+    ///the argument of the output
+    Argument,
+}
+impl Gradient {
+    pub(crate) fn cycle(self) -> Self {
+        match self {
+            Self::None => Self::Parameter,
+            Self::Parameter => Self::Modulus,
+            Self::Modulus => Self::Argument,
+            Self::Argument => Self::None,
+        }
+    }
+}
+///what an animation does when it reaches the end of its range
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, Default, PartialEq)]
+pub enum Playback {
+    ///jumps back to the start
+    #[default]
+    Loop,
+    ///runs back and forth
+    Bounce,
+    ///pauses at the end
+    Once,
+}
+impl Playback {
+    pub(crate) fn cycle(self) -> Self {
+        match self {
+            Self::Loop => Self::Bounce,
+            Self::Bounce => Self::Once,
+            Self::Once => Self::Loop,
+        }
+    }
+}
+///sweeps a variable defined in the side bar, as in a=1, from start to end, see Graph::animate
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Animation {
+    ///name of the variable
+    pub var: String,
+    pub start: f64,
+    pub end: f64,
+    ///how many frames one sweep from start to end takes at Graph.fps, the speed of the animation
+    pub frames: usize,
+    pub playback: Playback,
+    pub playing: bool,
+    pub(crate) frame: usize,
+    pub(crate) forward: bool,
+}
+impl Animation {
+    pub fn new(var: String, start: f64, end: f64, frames: usize, playback: Playback) -> Self {
+        Self {
+            var,
+            start,
+            end,
+            frames: frames.max(1),
+            playback,
+            playing: false,
+            frame: 0,
+            forward: true,
+        }
+    }
+    ///current value of the variable
+    pub fn value(&self) -> f64 {
+        let frames = self.frames.max(1);
+        self.start + (self.end - self.start) * self.frame.min(frames) as f64 / frames as f64
+    }
+    ///moves one frame along the direction of play, or back against it
+    pub(crate) fn step(&mut self, along: bool) {
+        let frames = self.frames.max(1);
+        match (self.playback, along) {
+            (Playback::Loop, true) => self.frame = (self.frame + 1) % (frames + 1),
+            (Playback::Loop, false) => self.frame = self.frame.checked_sub(1).unwrap_or(frames),
+            (Playback::Bounce, _) => {
+                if along && self.frame == if self.forward { frames } else { 0 } {
+                    self.forward = !self.forward
+                }
+                if self.forward == along {
+                    self.frame = (self.frame + 1).min(frames)
+                } else {
+                    self.frame = self.frame.saturating_sub(1)
+                }
+            }
+            (Playback::Once, true) => {
+                self.frame = (self.frame + 1).min(frames);
+                if self.frame == frames {
+                    self.playing = false
+                }
+            }
+            (Playback::Once, false) => self.frame = self.frame.saturating_sub(1),
+        }
+    }
+}
+///what Graph::record writes
+#[cfg(feature = "record")]
+#[derive(Clone, Debug, Copy, Default, PartialEq)]
+pub enum RecordFormat {
+    ///one looping animated gif, frames with more than 256 colors are quantized
+    #[default]
+    Gif,
+    ///one looping animated png
+    Apng,
+    ///one png per frame
+    PngSequence,
+}
+///what Graph::record changes between frames, before the per frame callback runs
+#[cfg(feature = "record")]
+#[derive(Clone, Debug, Copy, Default, PartialEq)]
+pub enum Sweep {
+    ///nothing, the callback does all the work
+    #[default]
+    None,
+    ///turns Graph.angle once around over the recording
+    Rotate,
+    ///moves the slice this many slices per frame, see Graph::advance_slice
+    Slice(f64),
+    ///steps Graph.animation one frame
+    Animation,
+}
+///why Graph::record failed
+#[cfg(feature = "record")]
+#[derive(Debug)]
+pub enum RecordError {
+    ///the width or height is zero, or too large for the format
+    Size,
+    ///a frame could not be read back from the canvas
+    Render,
+    ///the png encoder failed
+    Png(png::EncodingError),
+    ///the gif encoder failed
+    Gif(gif::EncodingError),
+}
+#[cfg(feature = "record")]
+impl From<png::EncodingError> for RecordError {
+    fn from(value: png::EncodingError) -> Self {
+        Self::Png(value)
+    }
+}
+#[cfg(feature = "record")]
+impl From<gif::EncodingError> for RecordError {
+    fn from(value: gif::EncodingError) -> Self {
+        Self::Gif(value)
+    }
+}
+#[cfg(feature = "record")]
+impl std::fmt::Display for RecordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Size => write!(f, "invalid recording size"),
+            Self::Render => write!(f, "could not read back a frame"),
+            Self::Png(e) => write!(f, "{e}"),
+            Self::Gif(e) => write!(f, "{e}"),
+        }
+    }
+}
+#[cfg(feature = "record")]
+impl std::error::Error for RecordError {}
+///a headless recording, see Graph::record
+#[cfg(feature = "record")]
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Recording {
+    pub width: u32,
+    pub height: u32,
+    pub frames: usize,
+    ///frames per second of the written animation
+    pub fps: f64,
+    pub format: RecordFormat,
+    pub sweep: Sweep,
+}
+#[cfg(feature = "record")]
+impl Recording {
+    pub fn new(width: u32, height: u32, frames: usize, fps: f64) -> Self {
+        Self {
+            width,
+            height,
+            frames,
+            fps,
+            format: RecordFormat::default(),
+            sweep: Sweep::default(),
+        }
+    }
+}
+///a 3d data vertex, as drawn, found near a point on screen by Graph::pick
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug)]
+pub struct Picked {
+    ///index into the data vector
+    pub data: usize,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    ///the sampled value the vertex was drawn from
+    pub value: Complex,
+}
+///a zero or pole found in a domain coloring data set
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ZeroPole {
+    ///index into the data vector
+    pub data: usize,
+    pub x: f64,
+    pub y: f64,
+    ///positive for a zero of that order, negative for a pole
+    pub order: i32,
+}
+///maps a value to its domain coloring color
+pub type DomainColor = Box<dyn Fn(&Complex) -> Color + Send + Sync>;
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Copy, Default)]
 pub enum DepthColor {
@@ -220,6 +816,11 @@ pub(crate) enum Change {
     Str((usize, usize), String, bool),
     Line(usize, bool, bool),
     None,
+    ///fill, style and secondary axis of the nth name before they were changed,
+    ///after None so histories of older saves keep their meaning
+    Look(usize, Fill, Style, bool),
+    ///the animation before a side bar variable was animated
+    Animate(Option<Animation>),
 }
 #[cfg(feature = "arboard")]
 pub(crate) struct Clipboard(pub arboard::Clipboard);
@@ -273,9 +874,14 @@ pub struct Graph {
     #[cfg(feature = "skia-vulkan")]
     #[cfg_attr(feature = "serde", serde(skip))]
     pub renderer: Option<crate::skia_vulkan::renderer::VulkanRenderer>,
-    ///current data sets
+    ///current data sets, call data_modified after changing them in place
     #[cfg_attr(feature = "serde", serde(skip))]
     pub data: Vec<GraphData>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) binned: Vec<Option<Binned>>,
+    ///counts changes to Graph.data, the binned and decimated caches are kept per count
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) generation: u64,
     ///current data sets names for labeling, ordered by data
     #[cfg_attr(feature = "serde", serde(default))]
     pub names: Vec<Name>,
@@ -346,6 +952,27 @@ pub struct Graph {
     ///what slice we are currently at in any slice mode
     #[cfg_attr(feature = "serde", serde(default))]
     pub slice: isize,
+    ///advances the slice by slice_speed every frame of Graph.fps, see Graph::advance_slice
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub slice_playing: bool,
+    ///how many slices autoplay moves per frame of Graph.fps, may be fractional or negative,
+    ///with slice_play_var in 64ths of the var range
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub slice_speed: f64,
+    ///slice autoplay moves Graph.var instead of Graph.slice in flatten and depth
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub slice_play_var: bool,
+    ///how many previous slices stay drawn in lighter colors as the slice moves
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub onion_skin: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) slice_acc: f64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) slice_moved: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) ghost_next: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) ghosts: Vec<Vec<GraphData>>,
     ///var range used for flatten or depth
     #[cfg_attr(feature = "serde", serde(default))]
     pub var: Vec2,
@@ -358,6 +985,62 @@ pub struct Graph {
     ///alternate domain coloring mode
     #[cfg_attr(feature = "serde", serde(default))]
     pub domain_alternate: bool,
+    ///color scheme for domain coloring
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub domain_scheme: DomainColorScheme,
+    ///overrides domain_scheme when set
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub domain_color: Option<DomainColor>,
+    ///draws a hue wheel key for domain coloring in the bottom right corner
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub domain_wheel: bool,
+    ///sweeps a side bar variable each frame while playing, see Graph::animate
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub animation: Option<Animation>,
+    ///frames per second animations play at, going by InputState.time
+    #[cfg_attr(feature = "serde", serde(default = "default_fps"))]
+    pub fps: f64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) animation_acc: f64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) last_tick: Option<f64>,
+    ///colors curves in flatten and depth mode by a value along gradient_colors, with a color bar
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gradient: Gradient,
+    ///colormap for Graph.gradient, evenly spaced from the low to the high end
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gradient_colors: Vec<Color>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) gradient_range: Option<(f64, f64)>,
+    ///how many lines per direction conformal grid mode requests
+    #[cfg_attr(feature = "serde", serde(default = "default_conformal_lines"))]
+    pub conformal_lines: usize,
+    ///draws the source grid of conformal grid mode in a pane to the right
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub conformal_source: bool,
+    ///marks zeros with circles and poles with crosses in domain coloring, see Graph::zeros_poles
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub show_zeros_poles: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) zero_pole_cache: Option<Vec<ZeroPole>>,
+    ///draws a line along branch cuts in domain coloring, see Graph::branch_cuts
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub show_branch_cuts: bool,
+    ///smallest jump in argument between neighbouring samples, in radians, counted as a branch cut
+    #[cfg_attr(feature = "serde", serde(default = "default_branch_cut_threshold"))]
+    pub branch_cut_threshold: f64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) branch_cut_cache: Option<Vec<[Vec2; 2]>>,
+    ///vertices marked in 3d by clicking on them, see Graph::pick
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub pins: Vec<Picked>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) hover: Option<Picked>,
+    ///cursor and view the hover was picked at
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) hover_view: Option<(Vec2, Vec2, Vec3, Vec3, f64, Vec2)>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) pin_press: Option<Vec2>,
     #[cfg_attr(feature = "serde", serde(default))]
     pub(crate) screen: Vec2,
     #[cfg_attr(feature = "serde", serde(default))]
@@ -382,6 +1065,9 @@ pub struct Graph {
     ///colors of data sets for imag part, ordered by data
     #[cfg_attr(feature = "serde", serde(default))]
     pub alt_colors: Vec<Color>,
+    ///opacity of filled regions such as bands
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fill_alpha: u8,
     ///major ticks axis color
     #[cfg_attr(feature = "serde", serde(default))]
     pub axis_color: Color,
@@ -439,6 +1125,43 @@ pub struct Graph {
     ///current ruler position
     #[cfg_attr(feature = "serde", serde(default))]
     pub ruler_pos: Option<Vec2>,
+    ///dash pattern of the ruler
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ruler_dash: Dash,
+    ///the secondary y axis on the right reads y * secondary_scale + secondary_offset
+    ///of the primary y axis, alt scroll zooms it alone
+    #[cfg_attr(feature = "serde", serde(default = "default_secondary_scale"))]
+    pub secondary_scale: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub secondary_offset: f64,
+    ///title at the top of the plot
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub title: String,
+    ///x axis title, in 3d defaults to x
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub x_title: String,
+    ///y axis title, in 3d defaults to y
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub y_title: String,
+    ///z axis title of 3d plots, defaults to z
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub z_title: String,
+    ///text labels anchored to data coordinates, right click drag to move them
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub annotations: Vec<Annotation>,
+    ///annotation being dragged and where it was grabbed relative to its anchor on screen
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) annotation_drag: Option<(usize, Pos)>,
+    ///where and how the legend is drawn, click an entry to hide its graph
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub legend: Legend,
+    ///where the legend was grabbed relative to its corner, where it was pressed,
+    ///and the name under the press
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) legend_drag: Option<(Pos, Pos, Option<usize>)>,
+    ///data index of the legend entry under the mouse
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) legend_hover: Option<usize>,
     #[cfg_attr(feature = "serde", serde(default))]
     pub(crate) prec: f64,
     #[cfg_attr(feature = "serde", serde(default))]
@@ -455,6 +1178,8 @@ pub struct Graph {
     #[cfg_attr(feature = "serde", serde(default))]
     pub(crate) draw_offset: Pos,
     #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) pane_offset: Pos,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub(crate) cos_phi: f64,
     #[cfg_attr(feature = "serde", serde(default))]
     pub(crate) sin_phi: f64,
@@ -536,6 +1261,25 @@ pub struct Graph {
     pub(crate) font_cache: std::collections::HashMap<char, tiny_skia::Pixmap>,
     #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) image_buffer: Vec<u8>,
+    ///keeps the newest appended point in view, scrolling the x axis half a screen at a time
+    ///so the frames in between only draw the new points, 2d only
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub follow: bool,
+    ///if the next update may only draw newly appended points on top of the last frame
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) partial_redraw: bool,
+    ///offset, zoom and bound the last full frame was drawn with
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) drawn_view: (Vec2, Vec2, Vec2),
+    ///data index and index of the first point appended since the last update
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) appended: Vec<(usize, usize)>,
+    ///reduce large 2d data sets to their min/max per pixel column before drawing,
+    ///the reduction is redone when a data set is replaced, changes length, or the zoom changes
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub decimate: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) decimated: Vec<Option<(Decimation, GraphData)>>,
     #[cfg_attr(feature = "serde", serde(skip))]
     #[cfg(feature = "tiny-skia")]
     pub canvas: Option<tiny_skia::Pixmap>,
@@ -543,6 +1287,20 @@ pub struct Graph {
     #[cfg(all(feature = "skia", not(feature = "skia-vulkan")))]
     pub canvas: Option<skia_safe::Surface>,
 }
+///a grid of plots drawn into one surface, each pane keeps its own data, mode and view
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Subplots {
+    ///plots in the grid, filled row by row
+    pub panes: Vec<Graph>,
+    ///how many panes per row
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cols: usize,
+    ///pane which last received input, keys go here
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub active: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) size: Vec2,
+}
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Copy, PartialEq, Default)]
 pub enum Menu {
@@ -553,6 +1311,22 @@ pub enum Menu {
     #[cfg(feature = "serde")]
     Load,
 }
+#[cfg(feature = "serde")]
+fn default_fps() -> f64 {
+    60.0
+}
+#[cfg(feature = "serde")]
+fn default_conformal_lines() -> usize {
+    17
+}
+#[cfg(feature = "serde")]
+fn default_branch_cut_threshold() -> f64 {
+    0.5
+}
+#[cfg(feature = "serde")]
+fn default_secondary_scale() -> f64 {
+    1.0
+}
 impl Default for Graph {
     fn default() -> Self {
         #[cfg(all(any(feature = "skia", feature = "tiny-skia-text"), feature = "serde"))]
@@ -622,6 +1396,8 @@ impl Default for Graph {
             fast_3d: false,
             text_scroll_pos: (0, 0),
             data: Vec::new(),
+            binned: Vec::new(),
+            generation: 0,
             #[cfg(feature = "serde")]
             save_file: String::new(),
             #[cfg(feature = "serde")]
@@ -642,6 +1418,14 @@ impl Default for Graph {
             offset: Vec2::splat(0.0),
             angle: Vec2::splat(PI / 6.0),
             slice: 0,
+            slice_playing: false,
+            slice_speed: 1.0,
+            slice_play_var: false,
+            onion_skin: 0,
+            slice_acc: 0.0,
+            slice_moved: false,
+            ghost_next: false,
+            ghosts: Vec::new(),
             mult: 1.0,
             text_box: None,
             line_major: 8,
@@ -652,6 +1436,7 @@ impl Default for Graph {
             zoom: Vec2::splat(1.0),
             name_modified: false,
             draw_offset: Pos::new(0.0, 0.0),
+            pane_offset: Pos::new(0.0, 0.0),
             angle_type: Angle::Radian,
             mouse_held: false,
             menu: Menu::Normal,
@@ -667,6 +1452,33 @@ impl Default for Graph {
             anti_alias: true,
             lines: Lines::Lines,
             domain_alternate: true,
+            domain_scheme: DomainColorScheme::Default,
+            domain_color: None,
+            domain_wheel: false,
+            animation: None,
+            fps: 60.0,
+            animation_acc: 0.0,
+            last_tick: None,
+            gradient: Gradient::None,
+            gradient_colors: vec![
+                Color::new(68, 1, 84),
+                Color::new(59, 82, 139),
+                Color::new(33, 145, 140),
+                Color::new(94, 201, 98),
+                Color::new(253, 231, 37),
+            ],
+            gradient_range: None,
+            show_zeros_poles: false,
+            conformal_lines: 17,
+            conformal_source: false,
+            zero_pole_cache: None,
+            show_branch_cuts: false,
+            branch_cut_threshold: 0.5,
+            branch_cut_cache: None,
+            pins: Vec::new(),
+            hover: None,
+            hover_view: None,
+            pin_press: None,
             var: Vec2::new(-2.0, 2.0),
             #[cfg(any(feature = "skia", feature = "tiny-skia", feature = "wasm-draw"))]
             request_redraw: false,
@@ -689,6 +1501,7 @@ impl Default for Graph {
                 Color::new(0, 170, 170),
                 Color::new(170, 170, 0),
             ],
+            fill_alpha: 64,
             axis_color: Color::splat(0),
             axis_color_light: Color::splat(220),
             text_color,
@@ -707,6 +1520,18 @@ impl Default for Graph {
             side_height: 1.875,
             recalculate: false,
             ruler_pos: None,
+            ruler_dash: Dash::Dashed,
+            secondary_scale: 1.0,
+            secondary_offset: 0.0,
+            title: String::new(),
+            x_title: String::new(),
+            y_title: String::new(),
+            z_title: String::new(),
+            annotations: Vec::new(),
+            annotation_drag: None,
+            legend: Legend::default(),
+            legend_drag: None,
+            legend_hover: None,
             bracket_color: vec![
                 Color::new(255, 85, 85),
                 //Color::new(85, 255, 85),
@@ -723,7 +1548,13 @@ impl Default for Graph {
             keybinds: Some(Keybinds::default()),
             target_side_ratio: 3.0 / 2.0,
             min_side_width: 256.0,
-            select_color: Color::new(191, 191, 255),
+            select_color: Color::rgba(128, 128, 255, 96),
+            follow: false,
+            partial_redraw: false,
+            drawn_view: Default::default(),
+            appended: Vec::new(),
+            decimate: true,
+            decimated: Vec::new(),
             #[cfg(any(
                 all(feature = "skia", not(feature = "skia-vulkan")),
                 feature = "tiny-skia"
@@ -788,6 +1619,28 @@ pub struct Keybinds {
     pub show_box: Option<Keys>,
     ///toggles domain alternate mode, see Graph.domain_alternate for more info
     pub domain_alternate: Option<Keys>,
+    ///cycles Graph.domain_scheme
+    pub domain_scheme: Option<Keys>,
+    ///toggles the domain coloring hue wheel
+    pub domain_wheel: Option<Keys>,
+    ///plays or pauses Graph.animation
+    pub play: Option<Keys>,
+    ///pauses Graph.animation and moves it one frame forward
+    pub step_forward: Option<Keys>,
+    ///pauses Graph.animation and moves it one frame back
+    pub step_back: Option<Keys>,
+    ///cycles the playback of Graph.animation
+    pub playback: Option<Keys>,
+    ///cycles Graph.gradient
+    pub gradient: Option<Keys>,
+    ///toggles Graph.show_zeros_poles
+    pub zeros_poles: Option<Keys>,
+    ///toggles Graph.show_branch_cuts
+    pub branch_cuts: Option<Keys>,
+    ///toggles Graph.slice_playing
+    pub slice_play: Option<Keys>,
+    ///toggles Graph.onion_skin between off and 3 slices
+    pub onion_skin: Option<Keys>,
     ///iterates Graph.slice foward
     pub slice_up: Option<Keys>,
     ///iterates Graph.slice backward
@@ -798,6 +1651,18 @@ pub struct Keybinds {
     pub log_scale: Option<Keys>,
     ///toggles line style enum
     pub line_style: Option<Keys>,
+    ///in the side bar, cycles how the function under the cursor is filled
+    pub fill: Option<Keys>,
+    ///in the side bar, cycles how the points of the function under the cursor are connected
+    pub connect: Option<Keys>,
+    ///in the side bar, cycles the marker of the function under the cursor
+    pub marker: Option<Keys>,
+    ///in the side bar, cycles the dash pattern of the function under the cursor
+    pub dash: Option<Keys>,
+    ///in the side bar, moves the function under the cursor to or from the secondary y axis
+    pub secondary: Option<Keys>,
+    ///in the side bar, animates the variable under the cursor, as in a=1
+    pub animate: Option<Keys>,
     ///for flatten or depth graph modes, move the input variables range foward
     pub var_up: Option<Keys>,
     ///for flatten or depth graph modes, move the input variables range backward
@@ -872,6 +1737,10 @@ pub struct GraphTiny {
     pub view_x: bool,
     pub graph_mode: GraphMode,
     pub only_real: bool,
+    pub title: String,
+    pub x_title: String,
+    pub y_title: String,
+    pub z_title: String,
 }
 #[cfg(feature = "serde")]
 impl Graph {
@@ -905,6 +1774,10 @@ impl Graph {
             view_x: self.view_x,
             graph_mode: self.graph_mode,
             only_real: self.only_real,
+            title: self.title.clone(),
+            x_title: self.x_title.clone(),
+            y_title: self.y_title.clone(),
+            z_title: self.z_title.clone(),
         }
     }
     pub fn apply_tiny(&mut self, tiny: GraphTiny) {
@@ -926,6 +1799,10 @@ impl Graph {
         self.view_x = tiny.view_x;
         self.graph_mode = tiny.graph_mode;
         self.only_real = tiny.only_real;
+        self.title = tiny.title;
+        self.x_title = tiny.x_title;
+        self.y_title = tiny.y_title;
+        self.z_title = tiny.z_title;
         self.recalculate(None);
         self.name_modified(None);
         self.text_box = Some((0, 0));
@@ -943,11 +1820,7 @@ impl TryFrom<&String> for GraphTiny {
             .map_err(|_| ())?
             .parse::<usize>()
             .map_err(|_| ())?;
-        let comp = base64::prelude::BASE64_URL_SAFE_NO_PAD
-            .decode(b)
-            .map_err(|_| ())?;
-        let seri = zstd::bulk::decompress(&comp, l).map_err(|_| ())?;
-        bitcode::deserialize(&seri).map_err(|_| ())
+        crate::legacy::decode_tiny(b, l).ok_or(())
     }
 }
 impl Default for Keybinds {
@@ -1026,11 +1899,34 @@ impl Default for Keybinds {
             zoom_out_3d: Some(Keys::new(Key::Quote)),
             show_box: Some(Keys::new(Key::U)),
             domain_alternate: Some(Keys::new(Key::Y)),
+            domain_scheme: Some(Keys::new(Key::H)),
+            domain_wheel: Some(Keys::new(Key::W)),
+            zeros_poles: Some(Keys::new(Key::E)),
+            gradient: Some(Keys::new(Key::G)),
+            play: Some(Keys::new(Key::Space)),
+            step_forward: Some(Keys::new_with_modifier(
+                Key::Period,
+                Modifiers::default().ctrl(),
+            )),
+            step_back: Some(Keys::new_with_modifier(
+                Key::Comma,
+                Modifiers::default().ctrl(),
+            )),
+            playback: Some(Keys::new(Key::K)),
+            branch_cuts: Some(Keys::new(Key::J)),
+            slice_play: Some(Keys::new(Key::A)),
+            onion_skin: Some(Keys::new(Key::S)),
             slice_up: Some(Keys::new(Key::Period)),
             slice_down: Some(Keys::new(Key::Comma)),
             slice_view: Some(Keys::new(Key::Slash)),
             log_scale: Some(Keys::new_with_modifier(Key::L, Modifiers::default().ctrl())),
             line_style: Some(Keys::new(Key::L)),
+            fill: Some(Keys::new_with_modifier(Key::F, Modifiers::default().ctrl())),
+            connect: Some(Keys::new_with_modifier(Key::T, Modifiers::default().ctrl())),
+            marker: Some(Keys::new_with_modifier(Key::M, Modifiers::default().ctrl())),
+            dash: Some(Keys::new_with_modifier(Key::D, Modifiers::default().ctrl())),
+            secondary: Some(Keys::new_with_modifier(Key::R, Modifiers::default().ctrl())),
+            animate: Some(Keys::new_with_modifier(Key::N, Modifiers::default().ctrl())),
             var_up: Some(Keys::new_with_modifier(
                 Key::ArrowRight,
                 Modifiers::default().shift(),
@@ -1102,6 +1998,9 @@ pub struct InputState {
     pub pointer_right: Option<bool>,
     ///Some if multiple touch inputs have been detected
     pub multi: Option<Multi>,
+    ///seconds on any steady clock, when none outside of wasm the graph keeps its own
+    ///and in wasm every frame counts as one frame of Graph.fps
+    pub time: Option<f64>,
     #[cfg(any(feature = "egui", target_arch = "wasm32"))]
     pub clipboard_override: Option<String>,
 }
@@ -1115,6 +2014,7 @@ impl Default for InputState {
             pointer: None,
             pointer_right: None,
             multi: None,
+            time: None,
             #[cfg(any(feature = "egui", target_arch = "wasm32"))]
             clipboard_override: None,
         }
@@ -1203,6 +2103,7 @@ impl From<&egui::InputState> for InputState {
                 ),
                 zoom_delta: i.zoom_delta as f64,
             }),
+            time: Some(val.time),
             clipboard_override,
         }
     }
@@ -1299,26 +2200,47 @@ impl Modifiers {
     }
 }
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Copy, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[derive(Copy, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
     pub b: u8,
+    ///opacity, 255 is fully opaque
+    pub a: u8,
+}
+impl Default for Color {
+    fn default() -> Self {
+        Self::splat(0)
+    }
 }
 impl Color {
-    pub(crate) fn new(r: u8, g: u8, b: u8) -> Self {
-        Self { r, g, b }
+    ///opaque color
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 255 }
+    }
+    ///color with the given opacity
+    pub fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+    ///opaque gray
+    pub fn splat(c: u8) -> Self {
+        Self::new(c, c, c)
     }
-    pub(crate) fn splat(c: u8) -> Self {
-        Self { r: c, g: c, b: c }
+    ///same color with the given opacity
+    pub fn with_alpha(self, a: u8) -> Self {
+        Self { a, ..self }
     }
     #[cfg(feature = "wasm-draw")]
     pub(crate) fn to_col(self) -> String {
-        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        if self.a == 255 {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+        }
     }
     #[cfg(feature = "egui")]
     pub(crate) fn to_col(self) -> egui::Color32 {
-        egui::Color32::from_rgb(self.r, self.g, self.b)
+        egui::Color32::from_rgba_unmultiplied(self.r, self.g, self.b, self.a)
     }
     #[cfg(feature = "skia")]
     pub(crate) fn to_col(self) -> skia_safe::Color4f {
@@ -1326,15 +2248,15 @@ impl Color {
             self.r as f32 / 255.0,
             self.g as f32 / 255.0,
             self.b as f32 / 255.0,
-            1.0,
+            self.a as f32 / 255.0,
         )
     }
     #[cfg(feature = "tiny-skia")]
     pub(crate) fn to_col(self) -> tiny_skia::Color {
         #[cfg(target_arch = "wasm32")]
-        let c = tiny_skia::Color::from_rgba8(self.r, self.g, self.b, 255);
+        let c = tiny_skia::Color::from_rgba8(self.r, self.g, self.b, self.a);
         #[cfg(not(target_arch = "wasm32"))]
-        let c = tiny_skia::Color::from_rgba8(self.b, self.g, self.r, 255);
+        let c = tiny_skia::Color::from_rgba8(self.b, self.g, self.r, self.a);
         c
     }
 }
@@ -1374,6 +2296,12 @@ pub enum Complex {
     Complex(f64, f64),
 }
 impl Complex {
+    ///the point of the complex plane that stereographic projection sends to
+    ///a longitude and latitude of the riemann sphere, the north pole being infinity
+    pub fn from_sphere(lon: f64, lat: f64) -> Self {
+        let r = (PI / 4.0 + lat / 2.0).tan();
+        Complex::Complex(r * lon.cos(), r * lon.sin())
+    }
     pub fn to_options(self) -> (Option<f64>, Option<f64>) {
         match self {
             Complex::Real(y) => (Some(y), None),
@@ -1480,7 +2408,7 @@ impl From<(f64, f64)> for Complex {
     }
 }
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Copy, Debug, Clone, Default)]
+#[derive(Copy, Debug, Clone, PartialEq, Default)]
 pub struct Vec3 {
     pub x: f64,
     pub y: f64,
@@ -1594,14 +2522,14 @@ impl Div<f64> for Vec2 {
         Vec2::new(self.x / rhs, self.y / rhs)
     }
 }
-#[derive(Copy, Clone)]
-pub(crate) enum Align {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Align {
+    #[default]
     LeftBottom,
     LeftCenter,
     LeftTop,
-    #[allow(dead_code)]
     CenterBottom,
-    #[allow(dead_code)]
     CenterCenter,
     CenterTop,
     RightBottom,
@@ -2450,3 +3378,169 @@ impl From<&Key> for KeyStr {
         }
     }
 }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn default_keybinds_are_distinct() {
+        let Keybinds {
+            left,
+            right,
+            up,
+            down,
+            left_3d,
+            right_3d,
+            up_3d,
+            down_3d,
+            in_3d,
+            out_3d,
+            zoom_in,
+            zoom_out,
+            zoom_in_x,
+            zoom_out_x,
+            zoom_in_y,
+            zoom_out_y,
+            zoom_in_z,
+            zoom_out_z,
+            lines,
+            axis,
+            coord,
+            anti_alias,
+            ignore_bounds,
+            color_depth,
+            zoom_in_3d,
+            zoom_out_3d,
+            show_box,
+            domain_alternate,
+            domain_scheme,
+            domain_wheel,
+            play,
+            step_forward,
+            step_back,
+            playback,
+            gradient,
+            zeros_poles,
+            branch_cuts,
+            slice_play,
+            onion_skin,
+            slice_up,
+            slice_down,
+            slice_view,
+            log_scale,
+            line_style,
+            fill,
+            connect,
+            marker,
+            dash,
+            secondary,
+            animate,
+            var_up,
+            var_down,
+            var_in,
+            var_out,
+            prec_up,
+            prec_down,
+            ruler,
+            view,
+            mode_up,
+            mode_down,
+            reset,
+            side,
+            fast,
+            #[cfg(feature = "serde")]
+            save,
+            #[cfg(feature = "serde")]
+            full_save,
+            #[cfg(feature = "serde")]
+            paste,
+            settings,
+            #[cfg(feature = "serde")]
+            load,
+            #[cfg(any(feature = "skia", feature = "tiny-skia"))]
+            save_png,
+            only_real,
+            toggle_dark_mode,
+        } = Keybinds::default();
+        #[allow(unused_mut)]
+        let mut keys = vec![
+            left,
+            right,
+            up,
+            down,
+            left_3d,
+            right_3d,
+            up_3d,
+            down_3d,
+            in_3d,
+            out_3d,
+            zoom_in,
+            zoom_out,
+            zoom_in_x,
+            zoom_out_x,
+            zoom_in_y,
+            zoom_out_y,
+            zoom_in_z,
+            zoom_out_z,
+            lines,
+            axis,
+            coord,
+            anti_alias,
+            ignore_bounds,
+            color_depth,
+            zoom_in_3d,
+            zoom_out_3d,
+            show_box,
+            domain_alternate,
+            domain_scheme,
+            domain_wheel,
+            play,
+            step_forward,
+            step_back,
+            playback,
+            gradient,
+            zeros_poles,
+            branch_cuts,
+            slice_play,
+            onion_skin,
+            slice_up,
+            slice_down,
+            slice_view,
+            log_scale,
+            line_style,
+            fill,
+            connect,
+            marker,
+            dash,
+            secondary,
+            animate,
+            var_up,
+            var_down,
+            var_in,
+            var_out,
+            prec_up,
+            prec_down,
+            ruler,
+            view,
+            mode_up,
+            mode_down,
+            reset,
+            side,
+            fast,
+            settings,
+            only_real,
+            toggle_dark_mode,
+        ];
+        #[cfg(feature = "serde")]
+        keys.extend([save, full_save, paste, load]);
+        #[cfg(any(feature = "skia", feature = "tiny-skia"))]
+        keys.push(save_png);
+        let keys: Vec<_> = keys
+            .into_iter()
+            .flatten()
+            .map(|k| (k.key, k.modifiers.unwrap_or_default()))
+            .collect();
+        for (i, a) in keys.iter().enumerate() {
+            assert!(!keys[i + 1..].contains(a), "{a:?} is bound twice");
+        }
+    }
+}
@@ -2,18 +2,29 @@ use crate::types::{Color, Image, Pos, Vec2};
 #[cfg(all(feature = "serde", feature = "skia"))]
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "egui")]
-pub(crate) struct Painter<'a> {
-    painter: &'a egui::Painter,
+pub(crate) struct Painter {
+    painter: egui::Painter,
+    unclipped: egui::Rect,
     pub offset: Pos,
 }
 #[cfg(feature = "egui")]
-impl<'a> Painter<'a> {
-    pub(crate) fn new(ui: &'a egui::Ui, offset: Pos) -> Self {
+impl Painter {
+    pub(crate) fn new(ui: &egui::Ui, offset: Pos) -> Self {
         Self {
-            painter: ui.painter(),
+            painter: ui.painter().clone(),
+            unclipped: ui.painter().clip_rect(),
             offset,
         }
     }
+    ///only draws inside of the rect from p0 to p1 until unclip
+    pub(crate) fn clip(&mut self, p0: Pos, p1: Pos) {
+        let rect =
+            egui::Rect::from_points(&[(self.offset + p0).to_pos2(), (self.offset + p1).to_pos2()]);
+        self.painter.set_clip_rect(rect.intersect(self.unclipped));
+    }
+    pub(crate) fn unclip(&mut self) {
+        self.painter.set_clip_rect(self.unclipped);
+    }
     pub fn circle(&mut self, p0: Pos, r: f32, p2: &Color, p3: f32) {
         self.painter.circle_stroke(
             (self.offset + p0).to_pos2(),
@@ -61,11 +72,54 @@ impl<'a> Painter<'a> {
             egui::Stroke::new(width, p2.to_col()),
         );
     }
+    pub(crate) fn line_dashed(
+        &mut self,
+        p0: [Pos; 2],
+        width: f32,
+        p2: &Color,
+        dash: &[f32],
+        phase: f32,
+    ) {
+        let p0 = p0.map(|p| {
+            (self.offset
+                + Pos {
+                    x: p.x + 0.5,
+                    y: p.y + 0.5,
+                })
+            .to_pos2()
+        });
+        let (on, off): (Vec<f32>, Vec<f32>) = dash.chunks(2).map(|d| (d[0], d[1])).unzip();
+        self.painter.extend(egui::Shape::dashed_line_with_offset(
+            &p0,
+            egui::Stroke::new(width, p2.to_col()),
+            &on,
+            &off,
+            phase,
+        ));
+    }
     pub(crate) fn rect_filled(&self, p0: Pos, p2: &Color, p3: f32) {
         let rect =
             egui::Rect::from_center_size((self.offset + p0).to_pos2(), egui::Vec2::splat(p3));
         self.painter.rect_filled(rect, 0.0, p2.to_col());
     }
+    pub(crate) fn fill_rect(&mut self, p0: Pos, p1: Pos, color: &Color) {
+        let rect =
+            egui::Rect::from_points(&[(self.offset + p0).to_pos2(), (self.offset + p1).to_pos2()]);
+        self.painter.rect_filled(rect, 0.0, color.to_col());
+    }
+    pub(crate) fn fill_between(&mut self, top: &[Pos], bottom: &[Pos], color: &Color) {
+        let col = color.to_col();
+        let mut mesh = egui::Mesh::default();
+        for (a, b) in top.iter().zip(bottom) {
+            mesh.colored_vertex((self.offset + *a).to_pos2(), col);
+            mesh.colored_vertex((self.offset + *b).to_pos2(), col);
+        }
+        for i in 0..top.len().min(bottom.len()).saturating_sub(1) as u32 {
+            mesh.add_triangle(2 * i, 2 * i + 1, 2 * i + 2);
+            mesh.add_triangle(2 * i + 1, 2 * i + 3, 2 * i + 2);
+        }
+        self.painter.add(egui::Shape::mesh(mesh));
+    }
     pub(crate) fn image(&self, p0: &Image, pos: Vec2) {
         let d = egui::Rect::from_points(&[
             self.offset.to_pos2(),
@@ -134,6 +188,32 @@ impl<'a> Painter<'a> {
             offset,
         }
     }
+    #[cfg(not(feature = "skia-vulkan"))]
+    pub(crate) fn retained(
+        surface: &'a mut skia_safe::Surface,
+        anti_alias: bool,
+        offset: Pos,
+    ) -> Self {
+        Self {
+            surface,
+            anti_alias,
+            offset,
+        }
+    }
+    ///only draws inside of the rect from p0 to p1 until unclip
+    pub(crate) fn clip(&mut self, p0: Pos, p1: Pos) {
+        let (p0, p1) = (self.offset + p0, self.offset + p1);
+        let canvas = self.surface.canvas();
+        canvas.save();
+        canvas.clip_rect(
+            skia_safe::Rect::from_ltrb(p0.x, p0.y, p1.x, p1.y),
+            None,
+            false,
+        );
+    }
+    pub(crate) fn unclip(&mut self) {
+        self.surface.canvas().restore();
+    }
     pub(crate) fn line_segment(&mut self, p0: [Pos; 2], width: f32, p2: &Color) {
         let p0 = p0.map(|p| {
             self.offset
@@ -148,6 +228,27 @@ impl<'a> Painter<'a> {
             &make_paint(width, p2, true, false),
         );
     }
+    pub(crate) fn line_dashed(
+        &mut self,
+        p0: [Pos; 2],
+        width: f32,
+        p2: &Color,
+        dash: &[f32],
+        phase: f32,
+    ) {
+        let p0 = p0.map(|p| {
+            self.offset
+                + Pos {
+                    x: p.x + 0.5,
+                    y: p.y + 0.5,
+                }
+        });
+        let mut paint = make_paint(width, p2, true, false);
+        paint.set_path_effect(skia_safe::PathEffect::dash(dash, phase));
+        self.surface
+            .canvas()
+            .draw_line(p0[0].to_pos2(), p0[1].to_pos2(), &paint);
+    }
     pub fn circle(&mut self, p0: Pos, r: f32, p2: &Color, p3: f32) {
         self.surface.canvas().draw_circle(
             (self.offset + p0).to_pos2(),
@@ -192,6 +293,24 @@ impl<'a> Painter<'a> {
             buffer.copy_from_slice(px);
         }
     }
+    #[cfg(feature = "record")]
+    ///rgba bytes of the surface, which is bgra
+    pub(crate) fn rgba(&mut self) -> Vec<u8> {
+        self.surface
+            .canvas()
+            .peek_pixels()
+            .and_then(|pm| {
+                pm.pixels::<u32>().map(|px| {
+                    px.iter()
+                        .flat_map(|c| {
+                            let [b, g, r, a] = c.to_le_bytes();
+                            [r, g, b, a]
+                        })
+                        .collect()
+                })
+            })
+            .unwrap_or_default()
+    }
     pub(crate) fn save_img(&mut self, format: &ImageFormat) -> Data {
         Data {
             data: self
@@ -211,6 +330,32 @@ impl<'a> Painter<'a> {
             .canvas()
             .draw_point(p0.to_pos2(), &make_paint(p3, p2, true, true));
     }
+    pub(crate) fn fill_rect(&mut self, p0: Pos, p1: Pos, color: &Color) {
+        let (p0, p1) = (self.offset + p0, self.offset + p1);
+        let mut paint = make_paint(1.0, color, false, true);
+        paint.set_style(skia_safe::PaintStyle::Fill);
+        self.surface.canvas().draw_rect(
+            skia_safe::Rect::from_ltrb(
+                p0.x.min(p1.x),
+                p0.y.min(p1.y),
+                p0.x.max(p1.x),
+                p0.y.max(p1.y),
+            ),
+            &paint,
+        );
+    }
+    pub(crate) fn fill_between(&mut self, top: &[Pos], bottom: &[Pos], color: &Color) {
+        let points = top
+            .iter()
+            .chain(bottom.iter().rev())
+            .map(|p| (self.offset + *p).to_pos2())
+            .collect::<Vec<skia_safe::Point>>();
+        let mut paint = make_paint(1.0, color, self.anti_alias, true);
+        paint.set_style(skia_safe::PaintStyle::Fill);
+        self.surface
+            .canvas()
+            .draw_path(&skia_safe::Path::polygon(&points, true, None, None), &paint);
+    }
     pub(crate) fn image(&mut self, p0: &Image, pos: Vec2) {
         if self.anti_alias {
             let mut paint = skia_safe::Paint::default();
@@ -370,6 +515,7 @@ pub(crate) struct Painter {
     pub canvas: tiny_skia::Pixmap,
     anti_alias: bool,
     pub offset: Pos,
+    clip: Option<tiny_skia::Mask>,
 }
 #[cfg(feature = "tiny-skia")]
 impl Painter {
@@ -385,8 +531,37 @@ impl Painter {
             canvas,
             anti_alias,
             offset,
+            clip: None,
         }
     }
+    pub(crate) fn retained(anti_alias: bool, offset: Pos, canvas: tiny_skia::Pixmap) -> Self {
+        Self {
+            canvas,
+            anti_alias,
+            offset,
+            clip: None,
+        }
+    }
+    ///only draws inside of the rect from p0 to p1 until unclip
+    pub(crate) fn clip(&mut self, p0: Pos, p1: Pos) {
+        let (p0, p1) = (self.offset + p0, self.offset + p1);
+        let mut mask = tiny_skia::Mask::new(self.canvas.width(), self.canvas.height());
+        if let (Some(mask), Some(rect)) = (
+            mask.as_mut(),
+            tiny_skia::Rect::from_ltrb(p0.x, p0.y, p1.x, p1.y),
+        ) {
+            mask.fill_path(
+                &tiny_skia::PathBuilder::from_rect(rect),
+                tiny_skia::FillRule::Winding,
+                false,
+                tiny_skia::Transform::default(),
+            )
+        }
+        self.clip = mask
+    }
+    pub(crate) fn unclip(&mut self) {
+        self.clip = None
+    }
     pub(crate) fn line_segment(&mut self, p0: [Pos; 2], width: f32, p2: &Color) {
         let p0 = p0.map(|p| {
             self.offset
@@ -408,7 +583,41 @@ impl Painter {
             &make_paint(p2, true),
             &stroke,
             tiny_skia::Transform::default(),
-            None,
+            self.clip.as_ref(),
+        )
+    }
+    pub(crate) fn line_dashed(
+        &mut self,
+        p0: [Pos; 2],
+        width: f32,
+        p2: &Color,
+        dash: &[f32],
+        phase: f32,
+    ) {
+        let p0 = p0.map(|p| {
+            self.offset
+                + Pos {
+                    x: p.x + 0.5,
+                    y: p.y + 0.5,
+                }
+        });
+        let mut path = tiny_skia::PathBuilder::with_capacity(2, 2);
+        path.move_to(p0[0].x, p0[0].y);
+        path.line_to(p0[1].x, p0[1].y);
+        let Some(path) = path.finish() else {
+            return;
+        };
+        let stroke = tiny_skia::Stroke {
+            width,
+            dash: tiny_skia::StrokeDash::new(dash.to_vec(), phase),
+            ..Default::default()
+        };
+        self.canvas.stroke_path(
+            &path,
+            &make_paint(p2, true),
+            &stroke,
+            tiny_skia::Transform::default(),
+            self.clip.as_ref(),
         )
     }
     pub fn circle(&mut self, p0: Pos, r: f32, p2: &Color, width: f32) {
@@ -423,7 +632,7 @@ impl Painter {
                 ..Default::default()
             },
             tiny_skia::Transform::default(),
-            None,
+            self.clip.as_ref(),
         );
     }
     #[cfg(not(target_arch = "wasm32"))]
@@ -435,6 +644,15 @@ impl Painter {
         let slice: &[u32] = bytemuck::cast_slice(slice);
         buffer.copy_from_slice(slice);
     }
+    #[cfg(feature = "record")]
+    ///rgba bytes of the canvas, outside of wasm colors are drawn with red and blue swapped
+    pub(crate) fn rgba(&self) -> Vec<u8> {
+        #[allow(unused_mut)]
+        let mut data = self.canvas.data().to_vec();
+        #[cfg(not(target_arch = "wasm32"))]
+        data.chunks_exact_mut(4).for_each(|p| p.swap(0, 2));
+        data
+    }
     #[cfg(feature = "tiny-skia-png")]
     pub(crate) fn save_png(&mut self) -> Vec<u8> {
         self.canvas.encode_png().unwrap_or_default()
@@ -450,15 +668,55 @@ impl Painter {
             .unwrap(),
             &make_paint(p2, true),
             tiny_skia::Transform::default(),
-            None,
+            self.clip.as_ref(),
         );
     }
+    pub(crate) fn fill_rect(&mut self, p0: Pos, p1: Pos, color: &Color) {
+        let (p0, p1) = (self.offset + p0, self.offset + p1);
+        if let Some(rect) = tiny_skia::Rect::from_ltrb(
+            p0.x.min(p1.x),
+            p0.y.min(p1.y),
+            p0.x.max(p1.x),
+            p0.y.max(p1.y),
+        ) {
+            self.canvas.fill_rect(
+                rect,
+                &make_paint(color, false),
+                tiny_skia::Transform::default(),
+                self.clip.as_ref(),
+            );
+        }
+    }
+    pub(crate) fn fill_between(&mut self, top: &[Pos], bottom: &[Pos], color: &Color) {
+        let mut path = tiny_skia::PathBuilder::with_capacity(
+            top.len() + bottom.len() + 1,
+            top.len() + bottom.len(),
+        );
+        for (i, p) in top.iter().chain(bottom.iter().rev()).enumerate() {
+            let p = self.offset + *p;
+            if i == 0 {
+                path.move_to(p.x, p.y)
+            } else {
+                path.line_to(p.x, p.y)
+            }
+        }
+        path.close();
+        if let Some(path) = path.finish() {
+            self.canvas.fill_path(
+                &path,
+                &make_paint(color, self.anti_alias),
+                tiny_skia::FillRule::Winding,
+                tiny_skia::Transform::default(),
+                self.clip.as_ref(),
+            );
+        }
+    }
     pub(crate) fn highlight(&mut self, xi: f32, yi: f32, xf: f32, yf: f32, color: &Color) {
         self.canvas.fill_rect(
             tiny_skia::Rect::from_ltrb(xi, yi, xf, yf).unwrap(),
             &make_paint(color, false),
             tiny_skia::Transform::default(),
-            None,
+            self.clip.as_ref(),
         );
     }
     pub(crate) fn clear_offset(&mut self, screen: Vec2, background: &Color) {
@@ -466,7 +724,7 @@ impl Painter {
             tiny_skia::Rect::from_ltrb(0.0, 0.0, self.offset.x, screen.y as f32).unwrap(),
             &make_paint(background, false),
             tiny_skia::Transform::default(),
-            None,
+            self.clip.as_ref(),
         );
     }
     pub(crate) fn clear_below(&mut self, screen: Vec2, background: &Color) {
@@ -475,7 +733,7 @@ impl Painter {
                 .unwrap(),
             &make_paint(background, false),
             tiny_skia::Transform::default(),
-            None,
+            self.clip.as_ref(),
         );
     }
     pub(crate) fn image(&mut self, p0: &Image, pos: Vec2) {
@@ -491,7 +749,7 @@ impl Painter {
             p0.0.as_ref(),
             &paint,
             tiny_skia::Transform::from_row(sx, 0.0, 0.0, sy, self.offset.x, self.offset.y),
-            None,
+            self.clip.as_ref(),
         );
     }
     pub(crate) fn hline(&mut self, p0: f32, p1: f32, p3: &Color) {
@@ -505,7 +763,7 @@ impl Painter {
                 &make_paint(p3, false),
                 &tiny_skia::Stroke::default(),
                 tiny_skia::Transform::default(),
-                None,
+                self.clip.as_ref(),
             );
         }
     }
@@ -520,7 +778,7 @@ impl Painter {
                 &make_paint(p3, false),
                 &tiny_skia::Stroke::default(),
                 tiny_skia::Transform::default(),
-                None,
+                self.clip.as_ref(),
             );
         }
     }
@@ -543,7 +801,7 @@ impl Painter {
                 pm.as_ref(),
                 &paint,
                 transform,
-                None,
+                self.clip.as_ref(),
             );
             pxi += pm.width() as i32;
         }
@@ -672,12 +930,21 @@ extern "C" {
     #[cfg(feature = "wasm-draw")]
     fn line_segment(a: f64, b: f64, x: f64, y: f64, w: f64, c: &str);
     #[cfg(feature = "wasm-draw")]
+    #[allow(clippy::too_many_arguments)]
+    fn line_dashed(a: f64, b: f64, x: f64, y: f64, w: f64, c: &str, dash: &[f32], phase: f64);
+    #[cfg(feature = "wasm-draw")]
     fn circle(x: f64, y: f64, r1: f64, r2: f64, w: f64, c: &str);
     #[cfg(feature = "wasm-draw")]
     fn fill(c: &str);
     #[cfg(feature = "wasm-draw")]
     fn fill_rect(a: f64, b: f64, x: f64, y: f64, c: &str);
     #[cfg(feature = "wasm-draw")]
+    fn fill_polygon(points: &[f64], c: &str);
+    #[cfg(feature = "wasm-draw")]
+    fn clip(a: f64, b: f64, x: f64, y: f64);
+    #[cfg(feature = "wasm-draw")]
+    fn unclip();
+    #[cfg(feature = "wasm-draw")]
     fn text_bounds(s: &str) -> js_sys::Array;
     #[cfg(feature = "wasm-draw")]
     fn fill_text(s: &str, x: f64, y: f64, c: &str);
@@ -706,6 +973,18 @@ impl Painter {
         fill(&background.to_col());
         Self { anti_alias, offset }
     }
+    ///only draws inside of the rect from p0 to p1 until unclip
+    pub(crate) fn clip(&mut self, p0: Pos, p1: Pos) {
+        clip(
+            (self.offset.x + p0.x.min(p1.x)) as f64,
+            (self.offset.y + p0.y.min(p1.y)) as f64,
+            (p1.x - p0.x).abs() as f64,
+            (p1.y - p0.y).abs() as f64,
+        );
+    }
+    pub(crate) fn unclip(&mut self) {
+        unclip();
+    }
     pub(crate) fn line_segment(&mut self, p0: [Pos; 2], width: f32, p2: &Color) {
         let (a, b) = (
             (self.offset.x + p0[0].x + 0.5) as f64,
@@ -717,6 +996,24 @@ impl Painter {
         );
         line_segment(a, b, x, y, width as f64, &p2.to_col());
     }
+    pub(crate) fn line_dashed(
+        &mut self,
+        p0: [Pos; 2],
+        width: f32,
+        p2: &Color,
+        dash: &[f32],
+        phase: f32,
+    ) {
+        let (a, b) = (
+            (self.offset.x + p0[0].x + 0.5) as f64,
+            (self.offset.y + p0[0].y + 0.5) as f64,
+        );
+        let (x, y) = (
+            (self.offset.x + p0[1].x + 0.5) as f64,
+            (self.offset.y + p0[1].y + 0.5) as f64,
+        );
+        line_dashed(a, b, x, y, width as f64, &p2.to_col(), dash, phase as f64);
+    }
     pub fn circle(&mut self, p0: Pos, r: f32, p2: &Color, width: f32) {
         circle(
             (self.offset.x + p0.x + 0.5) as f64,
@@ -736,6 +1033,23 @@ impl Painter {
             &p2.to_col(),
         );
     }
+    pub(crate) fn fill_rect(&mut self, p0: Pos, p1: Pos, color: &Color) {
+        fill_rect(
+            (self.offset.x + p0.x.min(p1.x)) as f64,
+            (self.offset.y + p0.y.min(p1.y)) as f64,
+            (p1.x - p0.x).abs() as f64,
+            (p1.y - p0.y).abs() as f64,
+            &color.to_col(),
+        );
+    }
+    pub(crate) fn fill_between(&mut self, top: &[Pos], bottom: &[Pos], color: &Color) {
+        let points = top
+            .iter()
+            .chain(bottom.iter().rev())
+            .flat_map(|p| [(self.offset.x + p.x) as f64, (self.offset.y + p.y) as f64])
+            .collect::<Vec<f64>>();
+        fill_polygon(&points, &color.to_col());
+    }
     pub(crate) fn highlight(&mut self, xi: f32, yi: f32, xf: f32, yf: f32, color: &Color) {
         fill_rect(
             xi as f64,